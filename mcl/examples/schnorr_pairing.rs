@@ -1,30 +1,31 @@
-use mcl::{init, bn::*, traits::*, common::Base};
+#![allow(non_snake_case)]
+
+use mcl::{init, bn::*, traits::*, common::IoMode, secret::SecretFr};
 
 fn main() {
     // Always initialize the library first.
-    init::init_curve(init::Curve::Bls12_381);
+    init::init_curve(init::Curve::Bls12_381).unwrap();
 
-    // choose the generators for both of the groups
-    let g = G1::hash_and_map(b"something").unwrap();
+    // choose the generator of G2
     let g2 = G2::hash_and_map(b"something else").unwrap();
 
     // setup the keys
-    let a = Fr::from_csprng();
+    let a = SecretFr::from_csprng();
     let A = &g2 * &a;
 
     // initialize ephemerals (done by the Prover)
     let x = Fr::from_csprng();
-    let X = &g2 * &x;
+    let X = g2.mul_ct(&x);
 
     // generate challenge (done by the Verifier)
     let c = Fr::from_csprng();
 
     // compute the response (done by the Prover)
-    let mut U = X.get_str(Base::Dec);
-    U.push_str(&c.get_str(Base::Dec));
+    let mut U = X.get_str(IoMode::DEC).unwrap();
+    U.push_str(&c.get_str(IoMode::DEC).unwrap());
     let U = G1::hash_and_map(U.as_bytes()).unwrap();
 
-    let s = x + &a * &c;
+    let s = x + a.expose_secret() * c;
 
     let S = &U * s;
 
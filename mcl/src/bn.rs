@@ -5,73 +5,365 @@
 //! ```
 //! // Schnorr identification scheme.
 //! // Prover wants to show to the Verifier that he knows the secret key,
-//! use mcl::{init, bn::{Fr, G1}};
+//! use mcl::{init, bn::{Fr, G1}, secret::SecretFr};
 //! 
 //! // Always initialize the library first.
-//! init::init_curve(init::Curve::Bls12_381);
+//! init::init_curve(init::Curve::Bls12_381).unwrap();
 //!
 //! // choose the generators for both of the groups
 //! let g = G1::hash_and_map(b"something").unwrap();
 //!
 //! // setup the keys
-//! let sk = Fr::from_csprng();
+//! let sk = SecretFr::from_csprng();
 //! let pk = &g * &sk;
 //!
 //! // initialize ephemerals (done by the Prover)
 //! let x = Fr::from_csprng();
-//! let commitment = &g * &x;
+//! let commitment = g.mul_ct(&x);
 //!
 //! // generate challenge (done by the Verifier)
 //! let c = Fr::from_csprng();
 //!
 //! // compute the response (done by the Prover)
-//! let s = x + &sk * &c;
+//! let s = x + sk.expose_secret() * &c;
 //!
 //! // verify the proof (done by the Verifier)
 //! assert_eq!(&g * s, &commitment + pk * &c);
 //! ```
 
-use crate::{ffi::*, traits::*, common::Base};
+use crate::{ffi::*, traits::*, common::{self, IoMode, MapToMode, SerializationFormat}, error::MclError, init};
 
-use std::ops::{Add, Mul, Sub, Div};
+use std::convert::TryFrom;
+use std::iter::{Product, Sum};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 use mcl_derive::*;
 
-#[derive(Object, ScalarPoint, Random)]
+// All of the types are `repr(transparent)` so that slices of them can be
+// passed to the vectorized MCL functions without copying.
+
+#[derive(Object, ScalarPoint, PrimeField, Formattable, Random)]
 #[derive(Default, Debug, Clone, Copy)]
+#[repr(transparent)]
 pub struct Fp {
     inner: MclBnFp,
 }
 
-#[derive(Object, ScalarPoint)]
+/// An element `a + b * u` of the quadratic extension of `Fp`.
+#[derive(Object, ScalarPoint, Random)]
 #[derive(Default, Debug, Clone, Copy)]
+#[custom_fill]
+#[repr(transparent)]
 pub struct Fp2 {
     inner: MclBnFp2,
 }
 
-#[derive(Object, ScalarPoint, Formattable, Random)]
+#[derive(Object, ScalarPoint, PrimeField, Polynomial, Formattable, Random)]
 #[derive(Default, Debug, Clone, Copy)]
+#[repr(transparent)]
 pub struct Fr {
     inner: MclBnFr,
 }
 
-#[derive(Object, AdditivePoint, Formattable)]
+#[derive(Object, AdditivePoint, Polynomial, Formattable)]
 #[derive(Default, Debug, Clone)]
+#[affine_serialize]
+#[repr(transparent)]
 pub struct G1 {
     inner: MclBnG1,
 }
 
-#[derive(Object, AdditivePoint, Formattable)]
+#[derive(Object, AdditivePoint, Polynomial, Formattable)]
 #[derive(Default, Debug, Clone)]
+#[affine_serialize]
+#[repr(transparent)]
 pub struct G2 {
     inner: MclBnG2,
 }
 
 #[derive(Object, MultiplicativePoint, Formattable)]
 #[derive(Default, Debug, Clone)]
+#[repr(transparent)]
 pub struct GT {
     inner: MclBnGT,
 }
 
+impl Fp {
+    /// Map the element to a `G1` point using the map-to-curve function
+    /// of the current mode (see [`init::set_map_to_mode`]).
+    ///
+    /// # Errors
+    /// Returns [`MclError::MapToCurve`] if MCL fails to map the element.
+    pub fn map_to_g1(&self) -> Result<G1, MclError> {
+        common::with_map_to_mode(None, || self.map_to_g1_unlocked())?
+    }
+
+    fn map_to_g1_unlocked(&self) -> Result<G1, MclError> {
+        let mut result = G1::default();
+        let err = unsafe {
+            mclBnFp_mapToG1(&mut result.inner as *mut MclBnG1, &self.inner as *const MclBnFp)
+        };
+        match err {
+            0 => Ok(result),
+            _ => Err(MclError::MapToCurve),
+        }
+    }
+}
+
+impl Fp2 {
+    /// The element `a + b * u`.
+    pub fn new(a: Fp, b: Fp) -> Fp2 {
+        Fp2 {
+            inner: MclBnFp2 { d: [a.inner, b.inner] },
+        }
+    }
+
+    /// The `a` component of `a + b * u`.
+    pub fn real(&self) -> Fp {
+        Fp { inner: self.inner.d[0] }
+    }
+
+    /// The `b` component of `a + b * u`.
+    pub fn imag(&self) -> Fp {
+        Fp { inner: self.inner.d[1] }
+    }
+
+    /// The sign of the element, `sgn0` of RFC 9380: the parity of `a`,
+    /// or of `b` if `a` is zero.
+    pub fn is_odd(&self) -> bool {
+        let a = self.real();
+        a.is_odd() || (a.is_zero() && self.imag().is_odd())
+    }
+
+    /// The conjugate `a - b * u`.
+    pub fn conjugate(&self) -> Fp2 {
+        Fp2::new(self.real(), self.imag().neg())
+    }
+
+    /// Raise the element to the power `p^power`.
+    ///
+    /// The Frobenius endomorphism of `Fp2` is the conjugation, so it's
+    /// the identity for even powers.
+    pub fn frobenius_map(&self, power: usize) -> Fp2 {
+        if power % 2 == 1 {
+            self.conjugate()
+        } else {
+            *self
+        }
+    }
+
+    /// Multiply the element by the non-residue `xi = xi_a + u` defining the
+    /// sextic extension of the current curve (see [`init::Curve::xi_a`]).
+    ///
+    /// # Panics
+    /// Panics if the library isn't initialized with a member of [`init::Curve`].
+    pub fn mul_by_nonresidue(&self) -> Fp2 {
+        let curve = init::current_curve().expect("MCL library is not initialized");
+        self * Fp2::new(Fp::from(curve.xi_a()), Fp::one())
+    }
+
+    /// The multiplicative identity.
+    ///
+    /// # Panics
+    /// Panics if the library isn't initialized.
+    pub fn one() -> Fp2 {
+        Fp2::new(Fp::one(), Fp::zero())
+    }
+
+    /// Map the element to a `G2` point using the map-to-curve function
+    /// of the current mode (see [`init::set_map_to_mode`]).
+    ///
+    /// # Errors
+    /// Returns [`MclError::MapToCurve`] if MCL fails to map the element.
+    pub fn map_to_g2(&self) -> Result<G2, MclError> {
+        common::with_map_to_mode(None, || self.map_to_g2_unlocked())?
+    }
+
+    fn map_to_g2_unlocked(&self) -> Result<G2, MclError> {
+        let mut result = G2::default();
+        let err = unsafe {
+            mclBnFp2_mapToG2(&mut result.inner as *mut MclBnG2, &self.inner as *const MclBnFp2)
+        };
+        match err {
+            0 => Ok(result),
+            _ => Err(MclError::MapToCurve),
+        }
+    }
+
+    pub fn from_str(buffer: &str, io_mode: IoMode) -> Result<Fp2, MclError> {
+        let mut result = Fp2::default();
+        result.set_str(buffer, io_mode)?;
+        Ok(result)
+    }
+
+    // MCL has no CSPRNG function for `Fp2`, so it's sampled component-wise.
+    fn fill_by_csprng(&mut self) -> bool {
+        let (mut a, mut b) = (Fp::default(), Fp::default());
+        let ok = a.fill_by_csprng() && b.fill_by_csprng();
+        *self = Fp2::new(a, b);
+        ok
+    }
+}
+
+// MCL has no string functions for `Fp2`, so the components are formatted
+// as `Fp`s separated by a space, as in the `Fp2` of MCL.
+impl Formattable for Fp2 {
+    fn set_str(&mut self, buffer: &str, io_mode: IoMode) -> Result<(), MclError> {
+        let mut parts = buffer.split_whitespace();
+        let (a, b) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(a), Some(b), None) => (a, b),
+            _ => return Err(MclError::InvalidEncoding),
+        };
+        let (mut real, mut imag) = (Fp::default(), Fp::default());
+        common::with_serialization_mode(None, || {
+            real.set_str_unlocked(a, io_mode)?;
+            imag.set_str_unlocked(b, io_mode)
+        })?;
+        *self = Fp2::new(real, imag);
+        Ok(())
+    }
+
+    fn get_str(&self, io_mode: IoMode) -> Result<String, MclError> {
+        common::with_serialization_mode(None, || {
+            let a = self.real().get_str_unlocked(io_mode)?;
+            let b = self.imag().get_str_unlocked(io_mode)?;
+            Ok(format!("{} {}", a, b))
+        })
+    }
+}
+
+impl std::str::FromStr for Fp2 {
+    type Err = MclError;
+
+    /// Parse the decimal representation, or the hexadecimal one
+    /// if the string starts with `0x`.
+    fn from_str(s: &str) -> Result<Fp2, MclError> {
+        match s.strip_prefix("0x") {
+            Some(hex) => Fp2::from_str(hex, IoMode::HEX),
+            None => Fp2::from_str(s, IoMode::DEC),
+        }
+    }
+}
+
+impl std::fmt::Display for Fp2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}", self.real(), self.imag())
+    }
+}
+
+impl std::fmt::LowerHex for Fp2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        write!(f, "{:x} {:x}", self.real(), self.imag())
+    }
+}
+
+impl std::fmt::UpperHex for Fp2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        write!(f, "{:X} {:X}", self.real(), self.imag())
+    }
+}
+
+// MCL keeps the elements fully reduced, so equal elements have equal limbs.
+impl ConstantTimeEq for Fr {
+    fn ct_eq(&self, other: &Fr) -> Choice {
+        self.inner.d[..].ct_eq(&other.inner.d[..])
+    }
+}
+
+impl Zeroize for Fr {
+    fn zeroize(&mut self) {
+        self.inner.d.zeroize();
+    }
+}
+
+impl G1 {
+    /// Encode `msg` as a point following the `encode_to_curve` function
+    /// of RFC 9380 with the domain separation tag `dst`, i.e. the
+    /// `BLS12381G1_XMD:SHA-256_SSWU_NU_` suite.
+    ///
+    /// Available for BLS12-381 only, see [`G1::hash_to_curve`] for the
+    /// uniform variant.
+    pub fn encode_to_curve(msg: &[u8], dst: &[u8]) -> Result<G1, MclError> {
+        let u = crate::hash_to_curve::hash_to_field(msg, dst, 1, 1)?;
+        common::with_map_to_mode(Some(MapToMode::HashToCurve), || u[0].map_to_g1_unlocked())?
+    }
+}
+
+impl G2 {
+    /// Encode `msg` as a point following the `encode_to_curve` function
+    /// of RFC 9380 with the domain separation tag `dst`, i.e. the
+    /// `BLS12381G2_XMD:SHA-256_SSWU_NU_` suite.
+    ///
+    /// Available for BLS12-381 only, see [`G2::hash_to_curve`] for the
+    /// uniform variant.
+    pub fn encode_to_curve(msg: &[u8], dst: &[u8]) -> Result<G2, MclError> {
+        let u = crate::hash_to_curve::hash_to_field(msg, dst, 1, 2)?;
+        let u = Fp2::new(u[0], u[1]);
+        common::with_map_to_mode(Some(MapToMode::HashToCurve), || u.map_to_g2_unlocked())?
+    }
+}
+
+/// A `G2` point with precomputed line coefficients of the Miller loop.
+///
+/// Preparing a point is worth it when it's used as the second argument
+/// of many pairings, e.g. a public key or a fixed generator.
+///
+/// The coefficients depend on the curve, so the pairing functions panic
+/// if a prepared point is used after [`init::switch_curve`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct G2Prepared {
+    coeffs: Vec<u64>,
+    curve_generation: usize,
+}
+
+impl G2Prepared {
+    /// Precompute the coefficients of `q`.
+    ///
+    /// # Errors
+    /// Returns [`MclError::NotInitialized`] if the library isn't initialized,
+    /// as the number of coefficients depends on the curve.
+    pub fn new(q: &G2) -> Result<G2Prepared, MclError> {
+        init::check_initialized()?;
+        let len = unsafe { mclBn_getUint64NumToPrecompute() } as usize;
+        let mut coeffs = vec![0u64; len];
+        unsafe {
+            mclBn_precomputeG2(coeffs.as_mut_ptr(), &q.inner as *const MclBnG2);
+        }
+        Ok(G2Prepared { coeffs, curve_generation: init::curve_generation() })
+    }
+
+    // The coefficients are read by MCL according to the size of the active
+    // curve, so using them after a switch could read out of bounds.
+    fn coeffs(&self) -> *const u64 {
+        assert_eq!(
+            self.curve_generation,
+            init::curve_generation(),
+            "G2Prepared used after the curve was switched"
+        );
+        assert_eq!(
+            self.coeffs.len(),
+            unsafe { mclBn_getUint64NumToPrecompute() } as usize,
+            "G2Prepared doesn't match the active curve"
+        );
+        self.coeffs.as_ptr()
+    }
+}
+
+impl TryFrom<&G2> for G2Prepared {
+    type Error = MclError;
+
+    fn try_from(q: &G2) -> Result<G2Prepared, MclError> {
+        G2Prepared::new(q)
+    }
+}
+
 impl GT {
     pub fn from_pairing(p: &G1, q: &G2) -> GT {
         let mut result = MclBnGT::default();
@@ -84,58 +376,629 @@ impl GT {
         }
         GT { inner: result }
     }
+
+    /// Compute the Miller loop of the pairing without the final exponentiation.
+    ///
+    /// `GT::from_pairing(p, q) == GT::miller_loop(p, q).final_exp()`.
+    pub fn miller_loop(p: &G1, q: &G2) -> GT {
+        let mut result = GT::default();
+        unsafe {
+            mclBn_millerLoop(
+                &mut result.inner as *mut MclBnGT,
+                &p.inner as *const MclBnG1,
+                &q.inner as *const MclBnG2,
+            );
+        }
+        result
+    }
+
+    /// Compute the product of the Miller loops of all `(ps[i], qs[i])` pairs.
+    ///
+    /// # Errors
+    /// Returns [`MclError::LengthMismatch`] if the slices have different lengths.
+    pub fn miller_loop_vec(ps: &[G1], qs: &[G2]) -> Result<GT, MclError> {
+        if ps.len() != qs.len() {
+            return Err(MclError::LengthMismatch);
+        }
+        let mut result = GT::default();
+        unsafe {
+            if ps.is_empty() {
+                mclBnGT_setInt(&mut result.inner as *mut MclBnGT, 1);
+            } else {
+                mclBn_millerLoopVec(
+                    &mut result.inner as *mut MclBnGT,
+                    ps.as_ptr() as *const MclBnG1,
+                    qs.as_ptr() as *const MclBnG2,
+                    ps.len(),
+                );
+            }
+        }
+        Ok(result)
+    }
+
+    /// Apply the final exponentiation to the result of a Miller loop.
+    pub fn final_exp(&self) -> GT {
+        let mut result = GT::default();
+        unsafe {
+            mclBn_finalExp(&mut result.inner as *mut MclBnGT, &self.inner as *const MclBnGT);
+        }
+        result
+    }
+
+    /// Compute `prod e(p_i, q_i)` sharing a single final exponentiation.
+    pub fn multi_pairing(pairs: &[(G1, G2)]) -> GT {
+        let (ps, qs): (Vec<G1>, Vec<G2>) = pairs.iter().cloned().unzip();
+        GT::miller_loop_vec(&ps, &qs)
+            .expect("unzipped slices have equal lengths")
+            .final_exp()
+    }
+
+    /// Compute the pairing with a prepared second argument.
+    pub fn from_prepared_pairing(p: &G1, q: &G2Prepared) -> GT {
+        GT::miller_loop_prepared(p, q).final_exp()
+    }
+
+    /// Compute the Miller loop with a prepared second argument.
+    pub fn miller_loop_prepared(p: &G1, q: &G2Prepared) -> GT {
+        let mut result = GT::default();
+        unsafe {
+            mclBn_precomputedMillerLoop(
+                &mut result.inner as *mut MclBnGT,
+                &p.inner as *const MclBnG1,
+                q.coeffs(),
+            );
+        }
+        result
+    }
+
+    /// Compute `miller_loop(p1, q1) * miller_loop(p2, q2)` in a single call.
+    pub fn miller_loop_prepared2(p1: &G1, q1: &G2Prepared, p2: &G1, q2: &G2Prepared) -> GT {
+        let mut result = GT::default();
+        unsafe {
+            mclBn_precomputedMillerLoop2(
+                &mut result.inner as *mut MclBnGT,
+                &p1.inner as *const MclBnG1,
+                q1.coeffs(),
+                &p2.inner as *const MclBnG1,
+                q2.coeffs(),
+            );
+        }
+        result
+    }
+
+    /// Compute `miller_loop(p1, q1) * miller_loop(p2, q2)` in a single call,
+    /// where only `q2` is prepared.
+    pub fn miller_loop_mixed(p1: &G1, q1: &G2, p2: &G1, q2: &G2Prepared) -> GT {
+        let mut result = GT::default();
+        unsafe {
+            mclBn_precomputedMillerLoop2mixed(
+                &mut result.inner as *mut MclBnGT,
+                &p1.inner as *const MclBnG1,
+                &q1.inner as *const MclBnG2,
+                &p2.inner as *const MclBnG1,
+                q2.coeffs(),
+            );
+        }
+        result
+    }
+
+    /// Compute `prod e(p_i, q_i)` over both prepared and unprepared pairs,
+    /// sharing a single final exponentiation.
+    pub fn multi_pairing_mixed(prepared: &[(&G1, &G2Prepared)], pairs: &[(G1, G2)]) -> GT {
+        let (ps, qs): (Vec<G1>, Vec<G2>) = pairs.iter().cloned().unzip();
+        let mut result = GT::miller_loop_vec(&ps, &qs).expect("unzipped slices have equal lengths");
+        for chunk in prepared.chunks(2) {
+            result *= match chunk {
+                [(p1, q1), (p2, q2)] => GT::miller_loop_prepared2(p1, q1, p2, q2),
+                [(p, q)] => GT::miller_loop_prepared(p, q),
+                _ => unreachable!(),
+            };
+        }
+        result.final_exp()
+    }
+
+    /// Check whether `prod e(p_i, q_i)` equals one.
+    ///
+    /// This is the usual way to verify pairing equations, e.g. `e(a, b) == e(c, d)`
+    /// is equivalent to `pairing_check(&[(a, b), (-c, d)])`.
+    pub fn pairing_check(pairs: &[(G1, G2)]) -> bool {
+        GT::multi_pairing(pairs).is_one()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::init;
 
-    fn initialize() {
-        init::init_curve(init::Curve::Bls12_381);
-    }
-
-    fn run_test(inner: impl FnOnce() -> ()) {
-        initialize();
+    fn run_test(inner: impl FnOnce()) {
+        // other tests may switch the curve
+        let _guard = init::pin_curve(init::Curve::Bls12_381).unwrap();
         inner();
     }
 
     #[test]
     fn test_mcl_bn_fp_str() {
         run_test(|| {
-            let fr = Fr::from_str("123", Base::Dec);
-            assert_eq!(fr.get_str(Base::Dec), "123".to_string());
+            let fr = Fr::from_str("123", IoMode::DEC).unwrap();
+            assert_eq!(fr.get_str(IoMode::DEC).unwrap(), "123".to_string());
+        });
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        run_test(|| {
+            assert_eq!(Fr::from_str("12x", IoMode::DEC), Err(MclError::InvalidEncoding));
+            assert_eq!(G1::from_str("1 zz", IoMode::HEX), Err(MclError::InvalidEncoding));
+        });
+    }
+
+    #[test]
+    fn test_parse_and_format() {
+        run_test(|| {
+            let a: Fr = "255".parse().unwrap();
+            assert_eq!(a.to_string(), "255");
+            assert_eq!(format!("{:x}", a), "ff");
+            assert_eq!(format!("{:#X}", a), "0xFF");
+            assert_eq!("0xff".parse::<Fr>().unwrap(), a);
+
+            let p = G1::hash_and_map(b"abc").unwrap();
+            assert_eq!(p.to_string().parse::<G1>().unwrap(), p);
+        });
+    }
+
+    #[test]
+    fn test_bytes_conversions() {
+        run_test(|| {
+            let p = G2::hash_and_map(b"abc").unwrap();
+            let bytes: Vec<u8> = p.clone().into();
+            assert_eq!(G2::try_from(&bytes[..]).unwrap(), p);
+            assert_eq!(G2::try_from(bytes.clone()).unwrap(), p);
+
+            let mut trailing = bytes;
+            trailing.push(0);
+            assert_eq!(G2::try_from(trailing), Err(MclError::InvalidEncoding));
+        });
+    }
+
+    #[test]
+    fn test_from_uniform_bytes() {
+        run_test(|| {
+            // 2^512 - 1 is reduced modulo the order
+            let two_256 = Fr::from_le_bytes_mod_order(&[&[0u8; 32][..], &[1]].concat()).unwrap();
+            assert_eq!(Fr::from_uniform_bytes(&[0xff; 64]), two_256.sqr() - Fr::one());
+            let mut wide = [0u8; 64];
+            wide[..Fp::byte_size()].copy_from_slice(&Fp::from(-1i64).to_le_bytes());
+            assert_eq!(Fp::from_uniform_bytes(&wide) + Fp::one(), Fp::zero());
+        });
+    }
+
+    // Chi-squared test of the lowest byte and of the parity of the reduced
+    // values, the thresholds have a false positive rate of about 0.1%.
+    #[test]
+    fn test_from_uniform_bytes_is_unbiased() {
+        fn chi_squared(counts: &[u64], samples: u64) -> f64 {
+            let expected = samples as f64 / counts.len() as f64;
+            counts.iter().map(|&c| (c as f64 - expected).powi(2) / expected).sum()
+        }
+
+        run_test(|| {
+            let samples = 25_600;
+            let mut rng = crate::rng::DeterministicRng::from_seed(b"uniform");
+            let mut bytes = [0u8; 64];
+            let (mut fr_low, mut fp_low) = (vec![0u64; 256], vec![0u64; 256]);
+            let (mut fr_odd, mut fp_odd) = (vec![0u64; 2], vec![0u64; 2]);
+            for _ in 0..samples {
+                rng.fill_bytes(&mut bytes);
+                let x = Fr::from_uniform_bytes(&bytes);
+                fr_low[x.to_le_bytes()[0] as usize] += 1;
+                fr_odd[x.is_odd() as usize] += 1;
+                let y = Fp::from_uniform_bytes(&bytes);
+                fp_low[y.to_le_bytes()[0] as usize] += 1;
+                fp_odd[y.is_odd() as usize] += 1;
+            }
+            // 255 and 1 degrees of freedom
+            assert!(chi_squared(&fr_low, samples) < 330.5);
+            assert!(chi_squared(&fp_low, samples) < 330.5);
+            assert!(chi_squared(&fr_odd, samples) < 10.83);
+            assert!(chi_squared(&fp_odd, samples) < 10.83);
+        });
+    }
+
+    #[test]
+    fn test_io_modes() {
+        run_test(|| {
+            let a = Fr::from_str("255", IoMode::DEC).unwrap();
+            assert_eq!(a.get_str(IoMode::BIN).unwrap(), "11111111");
+            assert_eq!(a.get_str(IoMode::HEX | IoMode::PREFIX).unwrap(), "0xff");
+            assert_eq!(Fr::from_str("0b11111111", IoMode::BIN | IoMode::PREFIX).unwrap(), a);
+
+            let hex = a.get_str(IoMode::SERIALIZE_HEX_STR).unwrap();
+            let bytes = a.serialize_raw().unwrap();
+            let expected: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            assert_eq!(hex.to_lowercase(), expected);
+
+            let p = G1::hash_and_map(b"abc").unwrap();
+            let proj = p.get_str(IoMode::DEC | IoMode::EC_PROJ).unwrap();
+            assert_eq!(G1::from_str(&proj, IoMode::DEC | IoMode::EC_PROJ).unwrap(), p);
+        });
+    }
+
+    #[test]
+    fn test_integer_conversions() {
+        run_test(|| {
+            assert_eq!(Fr::from(156u64), Fr::from_str("156", IoMode::DEC).unwrap());
+            assert_eq!(Fr::from(-1i64) + Fr::from(1u64), Fr::default());
+            assert_eq!(Fr::from(u64::MAX).to_string(), u64::MAX.to_string());
+            assert_eq!(Fp::from(3i64) * Fp::from(4u64), Fp::from(12u64));
+        });
+    }
+
+    #[test]
+    fn test_byte_conversions() {
+        run_test(|| {
+            let a = Fr::from(0x0102u64);
+            let mut le = a.to_le_bytes();
+            assert_eq!(le.len(), Fr::byte_size());
+            assert_eq!(&le[..3], &[2, 1, 0]);
+            assert_eq!(Fr::from_le_bytes_mod_order(&le).unwrap(), a);
+            le.reverse();
+            assert_eq!(Fr::from_be_bytes_mod_order(&le).unwrap(), a);
+
+            // r - 1 + 2 == 1 (mod r)
+            let minus_one = Fr::from(-1i64).to_le_bytes();
+            let mut wide = minus_one.clone();
+            wide.extend(vec![0; minus_one.len()]);
+            assert_eq!(
+                Fr::from_le_bytes_mod_order(&wide).unwrap() + Fr::from(2u64),
+                Fr::from(1u64)
+            );
+            assert_eq!(
+                Fr::from_le_bytes_mod_order(&vec![0; 2 * Fr::byte_size() + 1]),
+                Err(MclError::InvalidEncoding)
+            );
+
+            let p = Fp::from(7u64);
+            assert_eq!(Fp::from_le_bytes_mod_order(&p.to_le_bytes()).unwrap(), p);
+            assert_eq!(p.to_le_bytes().len(), Fp::byte_size());
+
+            assert_eq!(Fr::hash_to_scalar(b"abc").unwrap(), Fr::hash_to_scalar(b"abc").unwrap());
+            assert_ne!(Fr::hash_to_scalar(b"abc").unwrap(), Fr::hash_to_scalar(b"abd").unwrap());
+        });
+    }
+
+    #[test]
+    fn test_square_roots() {
+        run_test(|| {
+            let four = Fp::from(4u64);
+            let root = four.sqrt().unwrap();
+            assert!(root == Fp::from(2u64) || root == Fp::from(-2i64));
+            assert_eq!(four.legendre(), 1);
+            assert_eq!(Fp::default().legendre(), 0);
+            assert_eq!(Fp::default().sqrt(), Some(Fp::default()));
+
+            // -1 is a non-residue in Fp of BLS12-381 (p = 3 mod 4)
+            let minus_one = Fp::from(-1i64);
+            assert!(!minus_one.is_square());
+            assert_eq!(minus_one.legendre(), -1);
+            assert_eq!(minus_one.sqrt(), None);
+
+            let x = Fr::from_csprng();
+            assert_eq!(x.sqr().sqrt().map(|r| r.sqr()), Some(x.sqr()));
+
+            assert!(Fp::from(3u64).is_odd());
+            assert!(!Fr::from(4u64).is_odd());
+        });
+    }
+
+    #[test]
+    fn test_fp2() {
+        run_test(|| {
+            let a = Fp::from_str("0x1f", IoMode::HEX | IoMode::PREFIX).unwrap();
+            assert_eq!(a, Fp::from(31u64));
+            assert_eq!(a.to_string(), "31");
+            assert_eq!("0x1f".parse::<Fp>().unwrap(), a);
+
+            let b = Fp2::new(a, Fp::from(2u64));
+            assert_eq!((b.real(), b.imag()), (a, Fp::from(2u64)));
+            assert_eq!(b.to_string(), "31 2");
+            assert_eq!(format!("{:x}", b), "1f 2");
+            assert_eq!("31 2".parse::<Fp2>().unwrap(), b);
+            assert_eq!(Fp2::from_str("1 2 3", IoMode::DEC), Err(MclError::InvalidEncoding));
+            assert!(b.is_odd());
+            assert!(!Fp2::new(Fp::from(2u64), Fp::one()).is_odd());
+            assert!(Fp2::new(Fp::zero(), Fp::one()).is_odd());
+            assert!(!Fp2::new(Fp::zero(), Fp::from(2u64)).is_odd());
+
+            let c = Fp2::from_csprng();
+            assert_eq!(c * c.conjugate(), Fp2::new(c.real().sqr() + c.imag().sqr(), Fp::zero()));
+            // the little-endian bytes of p, computed from p - 1
+            let mut p = Fp::from(-1i64).to_le_bytes();
+            for byte in p.iter_mut() {
+                let (sum, carry) = byte.overflowing_add(1);
+                *byte = sum;
+                if !carry {
+                    break;
+                }
+            }
+            assert_eq!(c.frobenius_map(1), c.pow_le_bytes(&p));
+            assert_eq!(c.frobenius_map(2), c);
+
+            // the twist of BLS12-381 is y^2 = x^3 + 4 * xi
+            let q = G2::hash_and_map(b"q").unwrap().get_str(IoMode::DEC).unwrap();
+            let coords: Vec<&str> = q.split(' ').collect();
+            let x = Fp2::from_str(&coords[1..3].join(" "), IoMode::DEC).unwrap();
+            let y = Fp2::from_str(&coords[3..5].join(" "), IoMode::DEC).unwrap();
+            let four = Fp2::new(Fp::from(4u64), Fp::zero());
+            assert_eq!(y.sqr(), x.sqr() * x + four.mul_by_nonresidue());
+        });
+    }
+
+    #[test]
+    fn test_map_to_curve() {
+        run_test(|| {
+            let p = Fp::from(5u64).map_to_g1().unwrap();
+            assert!(p.is_valid() && !p.is_zero());
+            assert_eq!(Fp::from(5u64).map_to_g1().unwrap(), p);
+
+            let q = Fp2::default().map_to_g2().unwrap();
+            assert!(q.is_valid());
+        });
+    }
+
+    // Test vectors of RFC 9380, appendices J.9.1, J.9.2, J.10.1 and J.10.2.
+    #[test]
+    fn test_hash_to_curve() {
+        fn g1(x: &str, y: &str) -> G1 {
+            G1::from_str(&format!("1 {} {}", x, y), IoMode::HEX).unwrap()
+        }
+        // MCL expects the `c0` coordinate first.
+        fn g2(x_c0: &str, x_c1: &str, y_c0: &str, y_c1: &str) -> G2 {
+            let s = format!("1 {} {} {} {}", x_c0, x_c1, y_c0, y_c1);
+            G2::from_str(&s, IoMode::HEX).unwrap()
+        }
+
+        run_test(|| {
+            let dst = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+            assert_eq!(
+                G1::hash_to_curve(b"", dst).unwrap(),
+                g1(
+                    "052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1",
+                    "08ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265",
+                )
+            );
+            assert_eq!(
+                G1::hash_to_curve(b"abc", dst).unwrap(),
+                g1(
+                    "03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903",
+                    "0b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d",
+                )
+            );
+
+            let dst = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_NU_";
+            assert_eq!(
+                G1::encode_to_curve(b"", dst).unwrap(),
+                g1(
+                    "184bb665c37ff561a89ec2122dd343f20e0f4cbcaec84e3c3052ea81d1834e192c426074b02ed3dca4e7676ce4ce48ba",
+                    "04407b8d35af4dacc809927071fc0405218f1401a6d15af775810e4e460064bcc9468beeba82fdc751be70476c888bf3",
+                )
+            );
+            assert_eq!(
+                G1::encode_to_curve(b"abc", dst).unwrap(),
+                g1(
+                    "009769f3ab59bfd551d53a5f846b9984c59b97d6842b20a2c565baa167945e3d026a3755b6345df8ec7e6acb6868ae6d",
+                    "1532c00cf61aa3d0ce3e5aa20c3b531a2abd2c770a790a2613818303c6b830ffc0ecf6c357af3317b9575c567f11cd2c",
+                )
+            );
+
+            let dst = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+            assert_eq!(
+                G2::hash_to_curve(b"", dst).unwrap(),
+                g2(
+                    "0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a",
+                    "05cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d",
+                    "0503921d7f6a12805e72940b963c0cf3471c7b2a524950ca195d11062ee75ec076daf2d4bc358c4b190c0c98064fdd92",
+                    "12424ac32561493f3fe3c260708a12b7c620e7be00099a974e259ddc7d1f6395c3c811cdd19f1e8dbf3e9ecfdcbab8d6",
+                )
+            );
+            assert_eq!(
+                G2::hash_to_curve(b"abc", dst).unwrap(),
+                g2(
+                    "02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6",
+                    "139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8",
+                    "1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48",
+                    "00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16",
+                )
+            );
+
+            let dst = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_NU_";
+            assert_eq!(
+                G2::encode_to_curve(b"", dst).unwrap(),
+                g2(
+                    "00e7f4568a82b4b7dc1f14c6aaa055edf51502319c723c4dc2688c7fe5944c213f510328082396515734b6612c4e7bb7",
+                    "126b855e9e69b1f691f816e48ac6977664d24d99f8724868a184186469ddfd4617367e94527d4b74fc86413483afb35b",
+                    "0caead0fd7b6176c01436833c79d305c78be307da5f6af6c133c47311def6ff1e0babf57a0fb5539fce7ee12407b0a42",
+                    "1498aadcf7ae2b345243e281ae076df6de84455d766ab6fcdaad71fab60abb2e8b980a440043cd305db09d283c895e3d",
+                )
+            );
+            assert_eq!(
+                G2::encode_to_curve(b"abc", dst).unwrap(),
+                g2(
+                    "108ed59fd9fae381abfd1d6bce2fd2fa220990f0f837fa30e0f27914ed6e1454db0d1ee957b219f61da6ff8be0d6441f",
+                    "0296238ea82c6d4adb3c838ee3cb2346049c90b96d602d7bb1b469b905c9228be25c627bffee872def773d5b2a2eb57d",
+                    "033f90f6057aadacae7963b0a0b379dd46750c1c94a6357c99b65f63b79e321ff50fe3053330911c56b6ceea08fee656",
+                    "153606c417e59fb331b7ae6bce4fbf7c5190c33ce9402b5ebe2b70e44fca614f3f1382a3625ed5493843d0b0a652fc3f",
+                )
+            );
+
+            // The global mode is left untouched.
+            assert_eq!(init::map_to_mode(), MapToMode::Original);
         });
     }
 
     #[test]
     fn test_fp_mul() {
         run_test(|| {
-            let a = Fr::from_str("12", Base::Dec);
-            let b = Fr::from_str("13", Base::Dec);
-            let c = Fr::from_str("156", Base::Dec);
+            let a = Fr::from_str("12", IoMode::DEC).unwrap();
+            let b = Fr::from_str("13", IoMode::DEC).unwrap();
+            let c = Fr::from_str("156", IoMode::DEC).unwrap();
             assert_eq!(a * b, c);
         });
     }
 
+    #[test]
+    fn test_assign_ops() {
+        run_test(|| {
+            let a = Fr::from_csprng();
+            let b = Fr::from_csprng();
+            let mut c = a;
+            c += b;
+            assert_eq!(c, a + b);
+            c -= &b;
+            assert_eq!(c, a);
+            c *= b;
+            assert_eq!(c, a * b);
+            c /= &b;
+            assert_eq!(c, a);
+            assert_eq!(-c, a.neg());
+
+            let p = G1::hash_and_map(b"p").unwrap();
+            let mut q = p.clone();
+            q += &p;
+            assert_eq!(q, p.dbl());
+            q -= p.clone();
+            assert_eq!(q, p);
+            q *= &a;
+            assert_eq!(q, &p * a);
+            assert!((-&q + &q).is_zero());
+
+            let e = GT::from_pairing(&p, &G2::hash_and_map(b"q").unwrap());
+            let mut f = e.clone();
+            f *= &e;
+            assert_eq!(f, &e * &e);
+        });
+    }
+
+    #[test]
+    fn test_sum_product() {
+        run_test(|| {
+            let xs: Vec<Fr> = (1..=4i64).map(Fr::from).collect();
+            assert_eq!(xs.iter().sum::<Fr>(), Fr::from(10i64));
+            assert_eq!(xs.into_iter().product::<Fr>(), Fr::from(24i64));
+            assert!(Vec::<Fp>::new().into_iter().sum::<Fp>().is_zero());
+            assert!(Vec::<Fp2>::new().iter().product::<Fp2>().is_one());
+            assert!(Fp::one().is_one() && Fr::zero().is_zero());
+
+            let p = G1::hash_and_map(b"p").unwrap();
+            assert_eq!(vec![p.clone(); 3].iter().sum::<G1>(), &p * Fr::from(3i64));
+            assert_eq!(G2::identity(), G2::zero());
+            assert!(Vec::<G2>::new().into_iter().sum::<G2>().is_zero());
+
+            let e = GT::from_pairing(&p, &G2::hash_and_map(b"q").unwrap());
+            assert_eq!(vec![e.clone(), e.clone()].into_iter().product::<GT>(), &e * &e);
+            assert!(GT::identity().is_one());
+        });
+    }
+
+    #[test]
+    fn test_pow_and_batch_invert() {
+        run_test(|| {
+            let a = Fr::from_csprng();
+            assert_eq!(a.pow_u64(0), Fr::one());
+            assert_eq!(a.pow_u64(5), a * a * a * a * a);
+            assert_eq!(a.pow(&Fr::from(1000u64)), a.pow_u64(1000));
+            // Fermat's little theorem
+            assert!(a.pow(&Fr::from(-1i64)).is_one());
+
+            let b = Fp2::new(Fp::from_csprng(), Fp::from_csprng());
+            assert_eq!(b.pow_u64(3), b * b * b);
+
+            let mut values: Vec<Fr> = (0..5).map(|_| Fr::from_csprng()).collect();
+            values[2] = Fr::zero();
+            let expected: Vec<Fr> = values.iter().map(Fr::inv).collect();
+            Fr::batch_invert(&mut values);
+            assert_eq!(values, expected);
+            assert!(values[2].is_zero());
+
+            let mut values = [Fp::from_csprng(), Fp::one()];
+            let expected = [values[0].inv(), Fp::one()];
+            Fp::batch_invert(&mut values);
+            assert_eq!(values, expected);
+            Fp::batch_invert(&mut []);
+        });
+    }
+
     #[test]
     fn test_g1_mul() {
         run_test(|| {
             let p = G1::hash_and_map(b"this").unwrap();
-            let x = Fr::from_str("123", Base::Dec);
+            let x = Fr::from_str("123", IoMode::DEC).unwrap();
             let y = p * x;
             let expected = G1::from_str(
                 "1 ea23afffe7e4eaeddbec067563e2387bac5c2354bd58f4346151db670e65c465f947789e5f82de9ba7567d0a289c658 cf01434515162c99815667f4a5515e20d407609702b9bc182155bcf23473960ec4de3b5b552285b3f1656948cfe3260",
-                Base::Hex);
+                IoMode::HEX).unwrap();
             assert_eq!(y, expected);
         });
     }
 
     #[test]
+    fn test_mul_vec() {
+        run_test(|| {
+            let points: Vec<G1> = (0..10u8)
+                .map(|i| G1::hash_and_map(&[i]).unwrap())
+                .collect();
+            let scalars: Vec<Fr> = (0..10).map(|_| Fr::from_csprng()).collect();
+
+            let expected: G1 = points.iter().zip(scalars.iter()).map(|(p, s)| p * s).sum();
+            assert_eq!(G1::mul_vec(&points, &scalars).unwrap(), expected);
+            assert_eq!(G1::mul_vec(&points, &scalars[1..]), Err(MclError::LengthMismatch));
+
+            let q = G2::hash_and_map(b"abc").unwrap();
+            let e = GT::from_pairing(&points[0], &q);
+            let expected = e.pow(&scalars[0]) * e.pow(&scalars[1]);
+            let bases = vec![e.clone(), e];
+            assert_eq!(GT::pow_vec(&bases, &scalars[..2]).unwrap(), expected);
+            assert_eq!(GT::pow_vec(&bases, &scalars), Err(MclError::LengthMismatch));
+        });
+    }
+
+    #[test]
+    fn test_lagrange_interpolation() {
+        run_test(|| {
+            // 2-out-of-3 sharing of f(x) = secret + a * x
+            let coeffs = [Fr::from_csprng(), Fr::from_csprng()];
+            let xs: Vec<Fr> = (1..=3u64).map(Fr::from).collect();
+            let ys: Vec<Fr> = xs.iter().map(|x| Fr::evaluate_polynomial(&coeffs, x)).collect();
+            assert_eq!(Fr::lagrange_interpolation(&xs[1..], &ys[1..]).unwrap(), coeffs[0]);
+
+            let g = G1::hash_and_map(b"g").unwrap();
+            let points: Vec<G1> = coeffs.iter().map(|c| &g * c).collect();
+            let shares: Vec<G1> = xs.iter().map(|x| G1::evaluate_polynomial(&points, x)).collect();
+            assert_eq!(shares[0], &g * ys[0]);
+            assert_eq!(G1::lagrange_interpolation(&xs[..2], &shares[..2]).unwrap(), points[0]);
+
+            let h = G2::hash_and_map(b"h").unwrap();
+            let points: Vec<G2> = coeffs.iter().map(|c| &h * c).collect();
+            let shares: Vec<G2> = xs.iter().map(|x| G2::evaluate_polynomial(&points, x)).collect();
+            assert_eq!(G2::lagrange_interpolation(&xs[..2], &shares[..2]).unwrap(), points[0]);
+
+            assert!(Fr::evaluate_polynomial(&[], &xs[0]).is_zero());
+            assert_eq!(Fr::lagrange_interpolation(&xs, &ys[1..]), Err(MclError::LengthMismatch));
+            assert_eq!(Fr::lagrange_interpolation(&[], &[]), Err(MclError::InvalidInterpolation));
+            let zero = [Fr::from(0u64)];
+            assert_eq!(Fr::lagrange_interpolation(&zero, &ys[..1]), Err(MclError::InvalidInterpolation));
+            let dup = [xs[0], xs[0]];
+            assert_eq!(Fr::lagrange_interpolation(&dup, &ys[..2]), Err(MclError::InvalidInterpolation));
+        });
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    // the references exercise the `T op &U` and `&T op &U` impls
+    #[allow(clippy::op_ref)]
     fn test_pairing() {
         run_test(|| {
-            let a = Fr::from_str("123", Base::Dec);
-            let b = Fr::from_str("456", Base::Dec);
+            let a = Fr::from_str("123", IoMode::DEC).unwrap();
+            let b = Fr::from_str("456", IoMode::DEC).unwrap();
             let P = G1::hash_and_map(b"abc").unwrap();
             let Q = G2::hash_and_map(b"abc").unwrap();
 
@@ -150,10 +1013,151 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_gt_ops() {
+        run_test(|| {
+            let p = G1::hash_and_map(b"p").unwrap();
+            let q = G2::hash_and_map(b"q").unwrap();
+            let a = Fr::from_csprng();
+            let e = GT::from_pairing(&p, &q);
+            let f = GT::from_pairing(&(&p * a), &q);
+
+            assert!((&e * e.inv()).is_one());
+            assert_eq!(e.inv(), e.inv_generic());
+            assert_eq!(e.sqr(), &e * &e);
+            assert_eq!(&f / &e, e.pow(&(a - Fr::one())));
+            let mut g = f.clone();
+            g /= e.clone();
+            g *= &e;
+            assert_eq!(g, f);
+            assert!((&e / &e).is_one() && !GT::one().is_zero());
+
+            // e(aP, Q) / e(P, aQ) == 1
+            assert!((f / GT::from_pairing(&p, &(&q * a))).is_one());
+
+            // the generic inverse is needed before the final exponentiation
+            let m = GT::miller_loop(&p, &q);
+            assert!((&m * m.inv_generic()).is_one());
+        });
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_split_pairing() {
+        run_test(|| {
+            let a = Fr::from_csprng();
+            let P = G1::hash_and_map(b"abc").unwrap();
+            let Q = G2::hash_and_map(b"abc").unwrap();
+            let aP = &P * a;
+            let aQ = &Q * a;
+
+            let e = GT::from_pairing(&P, &Q);
+            assert_eq!(GT::miller_loop(&P, &Q).final_exp(), e);
+            assert_eq!(GT::multi_pairing(&[(P.clone(), Q.clone())]), e);
+            assert_eq!(
+                GT::miller_loop_vec(std::slice::from_ref(&P), &[]).unwrap_err(),
+                MclError::LengthMismatch
+            );
+
+            assert!(GT::pairing_check(&[(aP.clone(), Q.clone()), (P.neg(), aQ.clone())]));
+            assert!(!GT::pairing_check(&[(aP, Q), (P, aQ)]));
+            assert!(GT::pairing_check(&[]));
+        });
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_prepared_pairing() {
+        run_test(|| {
+            let a = Fr::from_csprng();
+            let P = G1::hash_and_map(b"abc").unwrap();
+            let Q = G2::hash_and_map(b"abc").unwrap();
+            let aP = &P * a;
+            let aQ = &Q * a;
+            let Q_prep = G2Prepared::try_from(&Q).unwrap();
+            let aQ_prep = G2Prepared::new(&aQ).unwrap();
+
+            assert_eq!(GT::from_prepared_pairing(&P, &Q_prep), GT::from_pairing(&P, &Q));
+            assert_eq!(
+                GT::miller_loop_prepared2(&aP, &Q_prep, &P, &aQ_prep).final_exp(),
+                GT::multi_pairing(&[(aP.clone(), Q.clone()), (P.clone(), aQ.clone())])
+            );
+            assert_eq!(
+                GT::miller_loop_mixed(&aP, &Q, &P, &aQ_prep),
+                GT::miller_loop_prepared2(&aP, &Q_prep, &P, &aQ_prep)
+            );
+
+            let minus_P = P.neg();
+            let mixed = GT::multi_pairing_mixed(
+                &[(&aP, &Q_prep), (&minus_P, &aQ_prep), (&P, &Q_prep)],
+                &[(minus_P.clone(), Q.clone())],
+            );
+            assert!(mixed.is_one());
+        });
+    }
+
+    #[test]
+    fn test_validation() {
+        run_test(|| {
+            assert!(Fr::default().is_zero());
+            assert!(Fr::from_str("1", IoMode::DEC).unwrap().is_one());
+            assert!(!Fr::from_str("2", IoMode::DEC).unwrap().is_one());
+
+            let p = G1::hash_and_map(b"abc").unwrap();
+            assert!(p.is_valid() && p.is_valid_order() && !p.is_zero());
+            let zero = G1::default();
+            assert!(zero.is_zero());
+
+            let mut q = G1::default();
+            let bytes = p.serialize_raw().unwrap();
+            assert_eq!(q.deserialize_raw_checked(&bytes), Ok(bytes.len()));
+            assert_eq!(q, p);
+            assert_eq!(
+                q.deserialize_raw_checked(&zero.serialize_raw().unwrap()),
+                Err(MclError::IdentityPoint)
+            );
+            assert_eq!(q.deserialize_raw_checked(&bytes[1..]), Err(MclError::InvalidEncoding));
+            assert_eq!(q, p);
+
+            let e = GT::from_pairing(&p, &G2::hash_and_map(b"abc").unwrap());
+            assert!(!e.is_one() && !e.is_zero());
+            assert!(e.pow(&Fr::default()).is_one());
+        });
+    }
+
+    #[test]
+    fn test_serialization_formats() {
+        run_test(|| {
+            let one = Fr::from_str("1", IoMode::DEC).unwrap();
+            let mcl = one.serialize_with(SerializationFormat::Mcl).unwrap();
+            let eth = one.serialize_with(SerializationFormat::Eth).unwrap();
+            assert_eq!(mcl[0], 1);
+            assert_eq!(eth[eth.len() - 1], 1);
+            assert!(!init::eth_serialization());
+
+            let p = G1::hash_and_map(b"abc").unwrap();
+            for format in &[
+                SerializationFormat::Mcl,
+                SerializationFormat::Eth,
+                SerializationFormat::EthUncompressed,
+            ] {
+                let bytes = p.serialize_with(*format).unwrap();
+                let mut q = G1::default();
+                assert_eq!(q.deserialize_with(&bytes, *format), Ok(bytes.len()));
+                assert_eq!(q, p);
+            }
+            let compressed = p.serialize_with(SerializationFormat::Eth).unwrap();
+            let uncompressed = p.serialize_with(SerializationFormat::EthUncompressed).unwrap();
+            assert_eq!(uncompressed.len(), 2 * compressed.len());
+            // the compression flag of ZCash
+            assert_eq!(compressed[0] & 0x80, 0x80);
+        });
+    }
+
     #[test]
     fn test_serde_raw() {
         run_test(|| {
-            let a = Fr::from_str("123", Base::Dec);
+            let a = Fr::from_str("123", IoMode::DEC).unwrap();
             let mut after = Fr::default();
             after.deserialize_raw(&a.serialize_raw().unwrap()).unwrap();
             assert_eq!(a, after);
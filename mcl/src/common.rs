@@ -1,7 +1,262 @@
+use crate::error::MclError;
+use crate::traits::RngSource;
+use crate::ffi::{
+    mclBn_getETHserialization, mclBn_setETHserialization, mclBn_setMapToMode, mclBn_setRandFunc,
+    MCLBN_IO_EC_AFFINE_SERIALIZE, MCL_MAP_TO_MODE_HASH_TO_CURVE, MCL_MAP_TO_MODE_ORIGINAL,
+    MCL_MAP_TO_MODE_TRY_AND_INC,
+};
+use libc::{c_int, c_uint};
+use std::ops::{BitOr, BitOrAssign};
+use std::os::raw::c_void;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Mutex, MutexGuard, RwLock};
 
-#[derive(Debug)]
-pub enum Base {
-    Dec = 10,
-    Hex = 16,
+/// The `ioMode` parameter of the `mclBn<name>_getStr` and `mclBn<name>_setStr`
+/// functions.
+///
+/// A mode consists of a base (`BIN`, `DEC` or `HEX`) optionally combined
+/// with flags using `|`, e.g. `IoMode::HEX | IoMode::PREFIX` or
+/// `IoMode::DEC | IoMode::EC_PROJ`.
+///
+/// See `mcl/api.md` and `mcl/include/mcl/op.hpp` for the details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IoMode(c_int);
+
+impl IoMode {
+    /// Binary number without a prefix.
+    pub const BIN: IoMode = IoMode(2);
+    /// Decimal number without a prefix.
+    pub const DEC: IoMode = IoMode(10);
+    /// Hexadecimal number without a prefix.
+    pub const HEX: IoMode = IoMode(16);
+    /// Prepend `0b` to binary and `0x` to hexadecimal numbers.
+    pub const PREFIX: IoMode = IoMode(128);
+    /// Affine coordinates of a point (the default).
+    pub const EC_AFFINE: IoMode = IoMode(0);
+    /// Represent the `y` coordinate of a point with a single bit.
+    pub const EC_COMP_Y: IoMode = IoMode(256);
+    /// The byte sequence of [`crate::traits::RawSerializable::serialize_raw`].
+    ///
+    /// The output isn't a valid string, use the `RawSerializable` trait instead.
+    pub const SERIALIZE: IoMode = IoMode(512);
+    /// An alias of [`IoMode::SERIALIZE`].
+    pub const FIXED_SIZE_BYTE_SEQ: IoMode = IoMode::SERIALIZE;
+    /// Projective (Jacobian) coordinates of a point.
+    pub const EC_PROJ: IoMode = IoMode(1024);
+    /// [`IoMode::SERIALIZE`] encoded as a hex string.
+    pub const SERIALIZE_HEX_STR: IoMode = IoMode(2048);
+    /// Both affine coordinates of a point as a byte sequence.
+    pub const EC_AFFINE_SERIALIZE: IoMode = IoMode(MCLBN_IO_EC_AFFINE_SERIALIZE);
+
+    /// The raw value passed to MCL.
+    pub fn bits(self) -> c_int {
+        self.0
+    }
+
+    /// Check whether all flags of `other` are set in `self`.
+    pub fn contains(self, other: IoMode) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for IoMode {
+    type Output = IoMode;
+
+    fn bitor(self, other: IoMode) -> IoMode {
+        IoMode(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for IoMode {
+    fn bitor_assign(&mut self, other: IoMode) {
+        self.0 |= other.0;
+    }
+}
+
+/// The binary format used by [`crate::traits::RawSerializable::serialize_with`]
+/// and [`crate::traits::RawSerializable::deserialize_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerializationFormat {
+    /// The native MCL format (little-endian field elements).
+    Mcl,
+    /// The format used by Ethereum and ZCash (big-endian field elements,
+    /// compressed points with the ZCash flag bits).
+    Eth,
+    /// Like [`SerializationFormat::Eth`], but both affine coordinates of
+    /// `G1` and `G2` points are written. Other types are serialized
+    /// in the same way as in `Eth`.
+    EthUncompressed,
 }
 
+// The serialization mode of MCL is a global variable, every access
+// to it is guarded by this lock. Only changing the mode takes the write lock.
+static SERIALIZATION_LOCK: RwLock<()> = RwLock::new(());
+
+/// Run `f` while holding the lock of the global MCL serialization mode.
+///
+/// If `format` is given, the mode is switched for the duration of `f`
+/// and restored afterwards.
+pub(crate) fn with_serialization_mode<T>(
+    format: Option<SerializationFormat>,
+    f: impl FnOnce() -> T,
+) -> T {
+    let eth = format.map(|format| (format != SerializationFormat::Mcl) as c_int);
+    {
+        let _guard = SERIALIZATION_LOCK.read().unwrap_or_else(|e| e.into_inner());
+        if eth.is_none_or(|eth| eth == unsafe { mclBn_getETHserialization() }) {
+            return f();
+        }
+    }
+    let _guard = SERIALIZATION_LOCK.write().unwrap_or_else(|e| e.into_inner());
+    unsafe {
+        let previous = mclBn_getETHserialization();
+        mclBn_setETHserialization(eth.unwrap_or(previous));
+        let result = f();
+        mclBn_setETHserialization(previous);
+        result
+    }
+}
+
+/// Set the global serialization mode permanently.
+pub(crate) fn set_eth_serialization(enable: bool) {
+    let _guard = SERIALIZATION_LOCK.write().unwrap_or_else(|e| e.into_inner());
+    unsafe { mclBn_setETHserialization(enable as c_int) }
+}
+
+/// The function used by MCL to map field elements and hashed messages
+/// onto the curve, see `mclBn_setMapToMode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapToMode {
+    /// The original map of MCL, the default after initialization.
+    Original,
+    /// Try-and-increment of the `x` coordinate.
+    TryAndInc,
+    /// The simplified SWU map of RFC 9380, available for BLS12-381 only.
+    HashToCurve,
+}
+
+impl MapToMode {
+    /// The value passed to `mclBn_setMapToMode`.
+    pub fn id(self) -> c_int {
+        match self {
+            MapToMode::Original => MCL_MAP_TO_MODE_ORIGINAL,
+            MapToMode::TryAndInc => MCL_MAP_TO_MODE_TRY_AND_INC,
+            MapToMode::HashToCurve => MCL_MAP_TO_MODE_HASH_TO_CURVE,
+        }
+    }
+}
+
+// MCL doesn't expose the current map-to mode, so it's tracked here.
+// As with the serialization mode, every access is guarded by the lock.
+static MAP_TO_MODE: RwLock<MapToMode> = RwLock::new(MapToMode::Original);
+
+/// Run `f` while holding the lock of the global MCL map-to mode.
+///
+/// If `mode` is given, the mode is switched for the duration of `f`
+/// and restored afterwards.
+///
+/// # Errors
+/// Returns [`MclError::Ffi`] if MCL doesn't support `mode` for the current curve.
+pub(crate) fn with_map_to_mode<T>(
+    mode: Option<MapToMode>,
+    f: impl FnOnce() -> T,
+) -> Result<T, MclError> {
+    {
+        let current = MAP_TO_MODE.read().unwrap_or_else(|e| e.into_inner());
+        if mode.is_none_or(|mode| mode == *current) {
+            return Ok(f());
+        }
+    }
+    let current = MAP_TO_MODE.write().unwrap_or_else(|e| e.into_inner());
+    let mode = match mode {
+        Some(mode) if mode != *current => mode,
+        _ => return Ok(f()),
+    };
+    unsafe {
+        match mclBn_setMapToMode(mode.id()) {
+            0 => {}
+            code => return Err(MclError::Ffi(code)),
+        }
+        let result = f();
+        mclBn_setMapToMode(current.id());
+        Ok(result)
+    }
+}
+
+/// Set the map-to mode permanently.
+pub(crate) fn set_map_to_mode(mode: MapToMode) -> Result<(), MclError> {
+    let mut current = MAP_TO_MODE.write().unwrap_or_else(|e| e.into_inner());
+    match unsafe { mclBn_setMapToMode(mode.id()) } {
+        0 => {
+            *current = mode;
+            Ok(())
+        }
+        code => Err(MclError::Ffi(code)),
+    }
+}
+
+/// Get the current map-to mode.
+pub(crate) fn map_to_mode() -> MapToMode {
+    *MAP_TO_MODE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Forget the tracked map-to mode after MCL is (re-)initialized.
+pub(crate) fn reset_map_to_mode() {
+    *MAP_TO_MODE.write().unwrap_or_else(|e| e.into_inner()) = MapToMode::Original;
+}
+
+// The source installed with `init::set_rng_source`. MCL calls the random
+// function without any synchronization, so the lock is held by every
+// call of a `setByCSPRNG` function, see `with_rng_lock`.
+type GlobalRng = Option<Box<dyn RngSource + Send>>;
+
+static RNG: Mutex<GlobalRng> = Mutex::new(None);
+
+unsafe extern "C" fn read_rng<R: RngSource>(
+    rng: *mut c_void,
+    buf: *mut c_void,
+    size: c_uint,
+) -> c_uint {
+    let rng = &mut *(rng as *mut R);
+    let buf = std::slice::from_raw_parts_mut(buf as *mut u8, size as usize);
+    // unwinding into C is undefined behaviour, MCL treats 0 as a failure
+    match panic::catch_unwind(AssertUnwindSafe(|| rng.fill_bytes(buf))) {
+        Ok(()) => size,
+        Err(_) => 0,
+    }
+}
+
+// Point MCL at the global source, or its own CSPRNG if there is none.
+fn install_global_rng(global: &mut MutexGuard<GlobalRng>) {
+    unsafe {
+        match global.as_mut() {
+            Some(rng) => mclBn_setRandFunc(
+                rng as *mut Box<dyn RngSource + Send> as *mut c_void,
+                Some(read_rng::<Box<dyn RngSource + Send>>),
+            ),
+            None => mclBn_setRandFunc(std::ptr::null_mut(), None),
+        }
+    }
+}
+
+/// Run `f` while holding the lock of the random function of MCL.
+pub(crate) fn with_rng_lock<T>(f: impl FnOnce() -> T) -> T {
+    let _global = RNG.lock().unwrap_or_else(|e| e.into_inner());
+    f()
+}
+
+/// Run `f` while MCL reads its randomness from `rng`.
+pub(crate) fn with_rng<R: RngSource, T>(rng: &mut R, f: impl FnOnce() -> T) -> T {
+    let mut global = RNG.lock().unwrap_or_else(|e| e.into_inner());
+    unsafe { mclBn_setRandFunc(rng as *mut R as *mut c_void, Some(read_rng::<R>)) };
+    let result = f();
+    install_global_rng(&mut global);
+    result
+}
+
+/// Replace the random source of MCL, `None` restores its own CSPRNG.
+pub(crate) fn set_rng_source(rng: GlobalRng) {
+    let mut global = RNG.lock().unwrap_or_else(|e| e.into_inner());
+    *global = rng;
+    install_global_rng(&mut global);
+}
@@ -0,0 +1,68 @@
+//! The error type shared by all fallible operations of the crate.
+
+use std::fmt;
+
+/// An error returned by the high-level MCL wrappers.
+///
+/// The C API of MCL reports failures either by returning a non-zero
+/// `int` or by returning `0` as the number of bytes read/written.
+/// The wrappers translate those codes into the variants below based on
+/// the operation that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MclError {
+    /// The input (a string or a byte buffer) is not a valid encoding
+    /// of the requested object.
+    InvalidEncoding,
+    /// The decoded point doesn't lie on the curve.
+    NotOnCurve,
+    /// The decoded point isn't a member of the prime-order subgroup.
+    WrongSubgroup,
+    /// The decoded point is the point at infinity where it's not allowed.
+    IdentityPoint,
+    /// The output buffer was too small to hold the result.
+    BufferTooSmall,
+    /// The library hasn't been initialized with [`crate::init::init_curve`].
+    NotInitialized,
+    /// The linked MCL library can't handle the requested curve.
+    UnsupportedCurve,
+    /// The library is already initialized with another curve.
+    CurveMismatch,
+    /// The curve can't be switched because a [`crate::init::CurveGuard`] is alive.
+    CurveInUse,
+    /// Hashing a message onto the curve failed.
+    MapToCurve,
+    /// Slices passed to a batch operation have different lengths.
+    LengthMismatch,
+    /// The x-coordinates passed to a Lagrange interpolation are empty,
+    /// contain zero or aren't distinct.
+    InvalidInterpolation,
+    /// The random source failed or panicked while sampling.
+    RandomSource,
+    /// MCL returned an error code that has no more specific variant.
+    Ffi(i32),
+}
+
+impl fmt::Display for MclError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MclError::InvalidEncoding => write!(f, "invalid encoding of an MCL object"),
+            MclError::NotOnCurve => write!(f, "point is not on the curve"),
+            MclError::WrongSubgroup => write!(f, "point is not in the prime-order subgroup"),
+            MclError::IdentityPoint => write!(f, "point is the point at infinity"),
+            MclError::BufferTooSmall => write!(f, "buffer too small"),
+            MclError::NotInitialized => write!(f, "MCL library is not initialized"),
+            MclError::UnsupportedCurve => write!(f, "curve is not supported by the linked MCL library"),
+            MclError::CurveMismatch => write!(f, "MCL library is initialized with another curve"),
+            MclError::CurveInUse => write!(f, "curve is in use and can't be switched"),
+            MclError::MapToCurve => write!(f, "couldn't map the message to the curve"),
+            MclError::LengthMismatch => write!(f, "input slices have different lengths"),
+            MclError::InvalidInterpolation => {
+                write!(f, "interpolation points must be non-empty, non-zero and distinct")
+            }
+            MclError::RandomSource => write!(f, "the random source failed"),
+            MclError::Ffi(code) => write!(f, "MCL returned error code {}", code),
+        }
+    }
+}
+
+impl std::error::Error for MclError {}
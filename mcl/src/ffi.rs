@@ -1,10 +1,19 @@
 //! Bindings to all functions (WIP) defined in https://github.com/herumi/mcl/blob/master/api.md
 
-use libc::{c_int, size_t};
+use libc::{c_int, c_uint, size_t};
 use std::os::raw::{c_char, c_void};
 
 pub const BN254: i32 = 0;
+pub const BN381_1: i32 = 1;
+pub const BN462: i32 = 3;
 pub const BLS12_381: i32 = 5;
+pub const BLS12_461: i32 = 7;
+pub const BLS12_377: i32 = 8;
+
+pub const MCL_MAP_TO_MODE_ORIGINAL: i32 = 0;
+pub const MCL_MAP_TO_MODE_TRY_AND_INC: i32 = 1;
+pub const MCL_MAP_TO_MODE_HASH_TO_CURVE: i32 = 5;
+
 pub const MCLBN_FR_UNIT_SIZE: i32 = 4;
 pub const MCLBN_FP_UNIT_SIZE: i32 = 6;
 
@@ -21,12 +30,19 @@ pub const GT_CIPHER_SIZE: i32 = GT_SIZE * 4;
 
 pub const MCLBN_COMPILED_TIME_VAR: i32 = (MCLBN_FR_UNIT_SIZE * 10) + MCLBN_FP_UNIT_SIZE;
 
+/// The `IoEcAffineSerialize` io mode, serializing both affine coordinates of a point.
+pub const MCLBN_IO_EC_AFFINE_SERIALIZE: c_int = 4096;
+
 
 #[link(name = "mclbn384_256")]
 extern "C" {
     pub fn mclBn_init(curve: c_int, compiledTimeVar: c_int) -> c_int;
+    pub fn mclBn_setETHserialization(enable: c_int);
+    pub fn mclBn_getETHserialization() -> c_int;
     pub fn mclBnFr_setStr(x: *mut MclBnFr, buf: *const c_char, bufSize: size_t, ioMode: c_int)
         -> c_int;
+    pub fn mclBnFp_setStr(x: *mut MclBnFp, buf: *const c_char, bufSize: size_t, ioMode: c_int)
+        -> c_int;
     pub fn mclBnG1_setStr(x: *mut MclBnG1, buf: *const c_char, bufSize: size_t, ioMode: c_int)
         -> c_int;
     pub fn mclBnG2_setStr(x: *mut MclBnG2, buf: *const c_char, bufSize: size_t, ioMode: c_int)
@@ -39,6 +55,12 @@ extern "C" {
         x: *const MclBnFr,
         ioMode: c_int,
     ) -> size_t;
+    pub fn mclBnFp_getStr(
+        buf: *mut c_char,
+        maxBufSize: size_t,
+        x: *const MclBnFp,
+        ioMode: c_int,
+    ) -> size_t;
     pub fn mclBnG1_getStr(
         buf: *mut c_char,
         maxBufSize: size_t,
@@ -58,9 +80,28 @@ extern "C" {
         ioMode: c_int,
     ) -> size_t;
 
+    // Map a field element to a point
+    pub fn mclBnFp_mapToG1(y: *mut MclBnG1, x: *const MclBnFp) -> c_int;
+    pub fn mclBnFp2_mapToG2(y: *mut MclBnG2, x: *const MclBnFp2) -> c_int;
+
     // Hash and map
     pub fn mclBnG1_hashAndMapTo(x: *mut MclBnG1, buf: *const c_void, bufSize: size_t) -> c_int;
     pub fn mclBnG2_hashAndMapTo(x: *mut MclBnG2, buf: *const c_void, bufSize: size_t) -> c_int;
+    pub fn mclBnG1_hashAndMapToWithDst(
+        x: *mut MclBnG1,
+        buf: *const c_void,
+        bufSize: size_t,
+        dst: *const c_char,
+        dstSize: size_t,
+    ) -> c_int;
+    pub fn mclBnG2_hashAndMapToWithDst(
+        x: *mut MclBnG2,
+        buf: *const c_void,
+        bufSize: size_t,
+        dst: *const c_char,
+        dstSize: size_t,
+    ) -> c_int;
+    pub fn mclBn_setMapToMode(mode: c_int) -> c_int;
 
     // Arithmetic operations
     // Multiplication
@@ -96,6 +137,15 @@ extern "C" {
     pub fn mclBnFp_sqr(z: *mut MclBnFp, x: *const MclBnFp);
     pub fn mclBnFp2_sqr(z: *mut MclBnFp2, x: *const MclBnFp2);
 
+    // Square root, ret 0 if the root exists, -1 otherwise
+    pub fn mclBnFr_squareRoot(y: *mut MclBnFr, x: *const MclBnFr) -> c_int;
+    pub fn mclBnFp_squareRoot(y: *mut MclBnFp, x: *const MclBnFp) -> c_int;
+    pub fn mclBnFp2_squareRoot(y: *mut MclBnFp2, x: *const MclBnFp2) -> c_int;
+
+    // Parity of the integer representation
+    pub fn mclBnFr_isOdd(x: *const MclBnFr) -> c_int;
+    pub fn mclBnFp_isOdd(x: *const MclBnFp) -> c_int;
+
     // Negation
     pub fn mclBnFr_neg(z: *mut MclBnFr, x: *const MclBnFr);
     pub fn mclBnFp_neg(z: *mut MclBnFp, x: *const MclBnFp);
@@ -106,6 +156,14 @@ extern "C" {
     // Point multiplication by scalar
     pub fn mclBnG1_mul(z: *mut MclBnG1, x: *const MclBnG1, y: *const MclBnFr);
     pub fn mclBnG2_mul(z: *mut MclBnG2, x: *const MclBnG2, y: *const MclBnFr);
+    // constant-time variants for secret scalars
+    pub fn mclBnG1_mulCT(z: *mut MclBnG1, x: *const MclBnG1, y: *const MclBnFr);
+    pub fn mclBnG2_mulCT(z: *mut MclBnG2, x: *const MclBnG2, y: *const MclBnFr);
+
+    // Multi-scalar multiplication, z = sum x[i] * y[i]
+    // x may be normalized in place
+    pub fn mclBnG1_mulVec(z: *mut MclBnG1, x: *mut MclBnG1, y: *const MclBnFr, n: size_t);
+    pub fn mclBnG2_mulVec(z: *mut MclBnG2, x: *mut MclBnG2, y: *const MclBnFr, n: size_t);
 
     // Point addition
     pub fn mclBnG1_add(z: *mut MclBnG1, x: *const MclBnG1, y: *const MclBnG1);
@@ -117,6 +175,53 @@ extern "C" {
 
     // GT arithmetic
     pub fn mclBnGT_pow(z: *mut MclBnGT, x: *const MclBnGT, y: *const MclBnFr);
+    pub fn mclBnGT_powCT(z: *mut MclBnGT, x: *const MclBnGT, y: *const MclBnFr);
+    // z = prod x[i] ^ y[i]
+    pub fn mclBnGT_powVec(z: *mut MclBnGT, x: *const MclBnGT, y: *const MclBnFr, n: size_t);
+    pub fn mclBnGT_setInt(y: *mut MclBnGT, x: i64);
+    // the inverse of an element of the cyclotomic subgroup, i.e. the conjugate
+    pub fn mclBnGT_inv(y: *mut MclBnGT, x: *const MclBnGT);
+    pub fn mclBnGT_invGeneric(y: *mut MclBnGT, x: *const MclBnGT);
+    pub fn mclBnGT_div(z: *mut MclBnGT, x: *const MclBnGT, y: *const MclBnGT);
+    pub fn mclBnGT_sqr(y: *mut MclBnGT, x: *const MclBnGT);
+
+    // Lagrange interpolation and polynomial evaluation
+    pub fn mclBn_FrLagrangeInterpolation(
+        out: *mut MclBnFr,
+        xVec: *const MclBnFr,
+        yVec: *const MclBnFr,
+        k: size_t,
+    ) -> c_int;
+    pub fn mclBn_G1LagrangeInterpolation(
+        out: *mut MclBnG1,
+        xVec: *const MclBnFr,
+        yVec: *const MclBnG1,
+        k: size_t,
+    ) -> c_int;
+    pub fn mclBn_G2LagrangeInterpolation(
+        out: *mut MclBnG2,
+        xVec: *const MclBnFr,
+        yVec: *const MclBnG2,
+        k: size_t,
+    ) -> c_int;
+    pub fn mclBn_FrEvaluatePolynomial(
+        out: *mut MclBnFr,
+        cVec: *const MclBnFr,
+        cSize: size_t,
+        x: *const MclBnFr,
+    ) -> c_int;
+    pub fn mclBn_G1EvaluatePolynomial(
+        out: *mut MclBnG1,
+        cVec: *const MclBnG1,
+        cSize: size_t,
+        x: *const MclBnFr,
+    ) -> c_int;
+    pub fn mclBn_G2EvaluatePolynomial(
+        out: *mut MclBnG2,
+        cVec: *const MclBnG2,
+        cSize: size_t,
+        x: *const MclBnFr,
+    ) -> c_int;
 
     // equality functions
     pub fn mclBnG1_isEqual(x: *const MclBnG1, y: *const MclBnG1) -> c_int;
@@ -126,11 +231,91 @@ extern "C" {
     pub fn mclBnFr_isEqual(x: *const MclBnFr, y: *const MclBnFr) -> c_int;
     pub fn mclBnFp2_isEqual(x: *const MclBnFp2, y: *const MclBnFp2) -> c_int;
 
+    // validation functions
+    pub fn mclBnFr_isZero(x: *const MclBnFr) -> c_int;
+    pub fn mclBnFp_isZero(x: *const MclBnFp) -> c_int;
+    pub fn mclBnFp2_isZero(x: *const MclBnFp2) -> c_int;
+    pub fn mclBnG1_isZero(x: *const MclBnG1) -> c_int;
+    pub fn mclBnG2_isZero(x: *const MclBnG2) -> c_int;
+    pub fn mclBnGT_isZero(x: *const MclBnGT) -> c_int;
+    pub fn mclBnFr_isOne(x: *const MclBnFr) -> c_int;
+    pub fn mclBnFp_isOne(x: *const MclBnFp) -> c_int;
+    pub fn mclBnFp2_isOne(x: *const MclBnFp2) -> c_int;
+    pub fn mclBnGT_isOne(x: *const MclBnGT) -> c_int;
+    pub fn mclBnG1_isValid(x: *const MclBnG1) -> c_int;
+    pub fn mclBnG2_isValid(x: *const MclBnG2) -> c_int;
+    pub fn mclBnG1_isValidOrder(x: *const MclBnG1) -> c_int;
+    pub fn mclBnG2_isValidOrder(x: *const MclBnG2) -> c_int;
+
+    // enable/disable the subgroup check in deserialization and setStr
+    pub fn mclBn_verifyOrderG1(doVerify: c_int);
+    pub fn mclBn_verifyOrderG2(doVerify: c_int);
+
     // pairing
     pub fn mclBn_pairing(z: *mut MclBnGT, x: *const MclBnG1, y: *const MclBnG2);
+    pub fn mclBn_finalExp(y: *mut MclBnGT, x: *const MclBnGT);
+    pub fn mclBn_millerLoop(z: *mut MclBnGT, x: *const MclBnG1, y: *const MclBnG2);
+    // z = prod millerLoop(x[i], y[i])
+    pub fn mclBn_millerLoopVec(
+        z: *mut MclBnGT,
+        x: *const MclBnG1,
+        y: *const MclBnG2,
+        n: size_t,
+    );
+
+    // precomputed pairing
+    pub fn mclBn_getUint64NumToPrecompute() -> c_int;
+    pub fn mclBn_precomputeG2(Qbuf: *mut u64, Q: *const MclBnG2);
+    pub fn mclBn_precomputedMillerLoop(f: *mut MclBnGT, P: *const MclBnG1, Qbuf: *const u64);
+    pub fn mclBn_precomputedMillerLoop2(
+        f: *mut MclBnGT,
+        P1: *const MclBnG1,
+        Q1buf: *const u64,
+        P2: *const MclBnG1,
+        Q2buf: *const u64,
+    );
+    pub fn mclBn_precomputedMillerLoop2mixed(
+        f: *mut MclBnGT,
+        P1: *const MclBnG1,
+        Q1: *const MclBnG2,
+        P2: *const MclBnG1,
+        Q2buf: *const u64,
+    );
+
+    pub fn mclBnFr_setByCSPRNG(x: *mut MclBnFr) -> c_int;
+    pub fn mclBnFp_setByCSPRNG(x: *mut MclBnFp) -> c_int;
+    // `readFunc` returns the number of bytes written, null arguments
+    // restore the default generator
+    pub fn mclBn_setRandFunc(
+        self_: *mut c_void,
+        readFunc: Option<unsafe extern "C" fn(*mut c_void, *mut c_void, c_uint) -> c_uint>,
+    );
 
-    pub fn mclBnFr_setByCSPRNG(x: *mut MclBnFr);
-    pub fn mclBnFp_setByCSPRNG(x: *mut MclBnFp);
+    // Conversions from integers and bytes
+    pub fn mclBn_getFrByteSize() -> c_int;
+    pub fn mclBn_getFpByteSize() -> c_int;
+    pub fn mclBnFr_setInt(y: *mut MclBnFr, x: i64);
+    pub fn mclBnFp_setInt(y: *mut MclBnFp, x: i64);
+    pub fn mclBnFr_setInt32(y: *mut MclBnFr, x: c_int);
+    pub fn mclBnFp_setInt32(y: *mut MclBnFp, x: c_int);
+    // x = buf & ((1 << (bitLen(r) - 1)) - 1)
+    pub fn mclBnFr_setLittleEndian(x: *mut MclBnFr, buf: *const c_void, bufSize: size_t) -> c_int;
+    pub fn mclBnFp_setLittleEndian(x: *mut MclBnFp, buf: *const c_void, bufSize: size_t) -> c_int;
+    // x = buf mod r, bufSize must be at most twice the byte size of the field
+    pub fn mclBnFr_setLittleEndianMod(x: *mut MclBnFr, buf: *const c_void, bufSize: size_t)
+        -> c_int;
+    pub fn mclBnFp_setLittleEndianMod(x: *mut MclBnFp, buf: *const c_void, bufSize: size_t)
+        -> c_int;
+    pub fn mclBnFr_setBigEndianMod(x: *mut MclBnFr, buf: *const c_void, bufSize: size_t) -> c_int;
+    pub fn mclBnFp_setBigEndianMod(x: *mut MclBnFp, buf: *const c_void, bufSize: size_t) -> c_int;
+    // ret byte count without the trailing zeros, 0 == error
+    pub fn mclBnFr_getLittleEndian(buf: *mut c_void, maxBufSize: size_t, x: *const MclBnFr)
+        -> size_t;
+    pub fn mclBnFp_getLittleEndian(buf: *mut c_void, maxBufSize: size_t, x: *const MclBnFp)
+        -> size_t;
+    // x = hash(buf) mod r
+    pub fn mclBnFr_setHashOf(x: *mut MclBnFr, buf: *const c_void, bufSize: size_t) -> c_int;
+    pub fn mclBnFp_setHashOf(x: *mut MclBnFp, buf: *const c_void, bufSize: size_t) -> c_int;
 
     // Set to zero
     pub fn mclBnFp_clear(x: *mut MclBnFp);
@@ -168,13 +353,13 @@ pub struct MclBnFp {
 #[derive(Default, Debug, Clone, Copy)]
 #[repr(C)]
 pub struct MclBnFp2 {
-    d: [MclBnFp; 2],
+    pub(crate) d: [MclBnFp; 2],
 }
 
 #[derive(Default, Debug, Clone, Copy)]
 #[repr(C)]
 pub struct MclBnFr {
-    d: [u64; MCLBN_FR_UNIT_SIZE as usize],
+    pub(crate) d: [u64; MCLBN_FR_UNIT_SIZE as usize],
 }
 
 #[derive(Default, Debug, Clone, Copy)]
@@ -0,0 +1,136 @@
+//! Building blocks of the hashing to elliptic curves defined in RFC 9380.
+//!
+//! The complete `hash_to_curve` and `encode_to_curve` functions are
+//! available on [`crate::bn::G1`] and [`crate::bn::G2`].
+
+use crate::bn::Fp;
+use crate::error::MclError;
+
+use sha2::{Digest, Sha256};
+
+// Output and block size of SHA-256 in bytes.
+const B_IN_BYTES: usize = 32;
+const S_IN_BYTES: usize = 64;
+
+const OVERSIZE_DST_PREFIX: &[u8] = b"H2C-OVERSIZE-DST-";
+
+/// Expand `msg` into `len` uniformly random bytes using
+/// `expand_message_xmd` with SHA-256 and the domain separation tag `dst`.
+///
+/// Tags longer than 255 bytes are hashed as described in section 5.3.3
+/// of RFC 9380.
+///
+/// # Errors
+/// Returns [`MclError::InvalidEncoding`] if `len` is greater than 8160
+/// or the (hashed) `dst` is empty.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len: usize) -> Result<Vec<u8>, MclError> {
+    let hashed_dst;
+    let dst = if dst.len() > 255 {
+        hashed_dst = Sha256::new()
+            .chain_update(OVERSIZE_DST_PREFIX)
+            .chain_update(dst)
+            .finalize();
+        hashed_dst.as_slice()
+    } else {
+        dst
+    };
+    let ell = len.div_ceil(B_IN_BYTES);
+    if ell > 255 || dst.is_empty() {
+        return Err(MclError::InvalidEncoding);
+    }
+    let dst_suffix = [dst.len() as u8];
+
+    let b_0 = Sha256::new()
+        .chain_update([0u8; S_IN_BYTES])
+        .chain_update(msg)
+        .chain_update((len as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(dst)
+        .chain_update(dst_suffix)
+        .finalize();
+
+    let mut result = Vec::with_capacity(ell * B_IN_BYTES);
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(dst)
+        .chain_update(dst_suffix)
+        .finalize();
+    result.extend_from_slice(&b_i);
+    for i in 2..=ell {
+        let mut mixed = b_0;
+        mixed.iter_mut().zip(b_i.iter()).for_each(|(a, b)| *a ^= b);
+        b_i = Sha256::new()
+            .chain_update(mixed)
+            .chain_update([i as u8])
+            .chain_update(dst)
+            .chain_update(dst_suffix)
+            .finalize();
+        result.extend_from_slice(&b_i);
+    }
+    result.truncate(len);
+    Ok(result)
+}
+
+/// Hash `msg` into `count` elements of `Fp^degree` following the
+/// `hash_to_field` function of RFC 9380 with `expand_message_xmd`.
+///
+/// The coordinates of the elements are returned one after another.
+pub fn hash_to_field(
+    msg: &[u8],
+    dst: &[u8],
+    count: usize,
+    degree: usize,
+) -> Result<Vec<Fp>, MclError> {
+    crate::init::check_initialized()?;
+    // L = ceil((ceil(log2(p)) + k) / 8) with the security parameter k = 128.
+    let l = (Fp::byte_size() * 8 + 128).div_ceil(8);
+    let bytes = expand_message_xmd(msg, dst, count * degree * l)?;
+    bytes.chunks(l).map(Fp::from_be_bytes_mod_order).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DST: &[u8] = b"QUUX-V01-CS02-with-expander-SHA256-128";
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_expand_message_xmd() {
+        let vectors: [(&[u8], usize, &str); 4] = [
+            (b"", 0x20, "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"),
+            (b"abc", 0x20, "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"),
+            (
+                b"abcdef0123456789",
+                0x20,
+                "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1",
+            ),
+            (
+                b"",
+                0x80,
+                "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbe\
+                 e0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18\
+                 eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dc\
+                 c541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced",
+            ),
+        ];
+        for (msg, len, expected) in vectors.iter() {
+            assert_eq!(hex(&expand_message_xmd(msg, DST, *len).unwrap()), *expected);
+        }
+    }
+
+    #[test]
+    fn test_expand_message_xmd_long_dst() {
+        let mut dst = b"QUUX-V01-CS02-with-expander-SHA256-128-long-DST-".to_vec();
+        dst.extend_from_slice(&[b'1'; 208]);
+        assert_eq!(
+            hex(&expand_message_xmd(b"", &dst, 0x20).unwrap()),
+            "e8dc0c8b686b7ef2074086fbdd2f30e3f8bfbd3bdf177f73f04b97ce618a3ed3"
+        );
+        assert_eq!(expand_message_xmd(b"", DST, 256 * 32), Err(MclError::InvalidEncoding));
+    }
+}
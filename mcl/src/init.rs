@@ -1,15 +1,291 @@
-use crate::ffi::mclBn_init;
+use crate::common::{self, with_serialization_mode, MapToMode};
+use crate::error::MclError;
+use crate::traits::RngSource;
+use crate::ffi::{
+    mclBn_getETHserialization, mclBn_init, mclBn_verifyOrderG1,
+    mclBn_verifyOrderG2, BLS12_377, BLS12_381, BLS12_461, BN254, BN381_1, BN462,
+    MCLBN_COMPILED_TIME_VAR, MCLBN_FP_UNIT_SIZE, MCLBN_FR_UNIT_SIZE,
+};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError};
 
+/// The pairing-friendly curves supported by MCL.
+///
+/// MCL is built in several variants differing in the maximal size of the
+/// field elements, and a curve can be used only with a variant that is
+/// large enough for it:
+///
+/// | Curve       | MCL id | Smallest library  | Compiled time var |
+/// |-------------|--------|-------------------|-------------------|
+/// | `Bn254`     | 0      | `mclbn256`        | 44                |
+/// | `Bn381_1`   | 1      | `mclbn384`        | 66                |
+/// | `Bn462`     | 3      | `mclbn512`        | 88                |
+/// | `Bls12_381` | 5      | `mclbn384_256`    | 46                |
+/// | `Bls12_461` | 7      | `mclbn512`        | 88                |
+/// | `Bls12_377` | 8      | `mclbn384_256`    | 46                |
+///
+/// This crate links `mclbn384_256` (see [`crate::ffi::MCLBN_COMPILED_TIME_VAR`]),
+/// so only `Bn254`, `Bls12_381` and `Bls12_377` can be initialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Curve {
+    Bn254,
+    Bn381_1,
+    Bn462,
     Bls12_381,
+    Bls12_461,
+    Bls12_377,
+}
+
+impl Curve {
+    /// The curve identifier used by MCL.
+    pub fn id(self) -> i32 {
+        match self {
+            Curve::Bn254 => BN254,
+            Curve::Bn381_1 => BN381_1,
+            Curve::Bn462 => BN462,
+            Curve::Bls12_381 => BLS12_381,
+            Curve::Bls12_461 => BLS12_461,
+            Curve::Bls12_377 => BLS12_377,
+        }
+    }
+
+    /// The number of 64-bit limbs of the base field and of the scalar field
+    /// a library must support in order to handle the curve.
+    pub fn unit_sizes(self) -> (i32, i32) {
+        match self {
+            Curve::Bn254 => (4, 4),
+            Curve::Bn381_1 => (6, 6),
+            Curve::Bn462 => (8, 8),
+            Curve::Bls12_381 => (6, 4),
+            Curve::Bls12_461 => (8, 5),
+            Curve::Bls12_377 => (6, 4),
+        }
+    }
+
+    /// The integer `a` of the non-residue `a + u` used by MCL to build
+    /// the sextic extension over `Fp2`.
+    pub fn xi_a(self) -> i64 {
+        match self {
+            Curve::Bn462 => 2,
+            Curve::Bls12_377 => 0,
+            _ => 1,
+        }
+    }
+
+    /// The compiled time variable of the smallest MCL library supporting the curve.
+    ///
+    /// It's not the value to pass to [`mcl_bn_init`], which must be the one of
+    /// the linked library, [`MCLBN_COMPILED_TIME_VAR`].
+    pub fn min_library_compiled_time_var(self) -> i32 {
+        match self {
+            Curve::Bn254 => 44,
+            Curve::Bn381_1 => 66,
+            Curve::Bn462 | Curve::Bls12_461 => 88,
+            Curve::Bls12_381 | Curve::Bls12_377 => 46,
+        }
+    }
+
+    /// The curve with the given MCL identifier.
+    pub fn from_id(id: i32) -> Option<Curve> {
+        match id {
+            BN254 => Some(Curve::Bn254),
+            BN381_1 => Some(Curve::Bn381_1),
+            BN462 => Some(Curve::Bn462),
+            BLS12_381 => Some(Curve::Bls12_381),
+            BLS12_461 => Some(Curve::Bls12_461),
+            BLS12_377 => Some(Curve::Bls12_377),
+            _ => None,
+        }
+    }
+
+    /// Check whether the curve can be used with the linked MCL library.
+    pub fn is_supported(self) -> bool {
+        let (fp_unit_size, fr_unit_size) = self.unit_sizes();
+        fp_unit_size <= MCLBN_FP_UNIT_SIZE && fr_unit_size <= MCLBN_FR_UNIT_SIZE
+    }
+}
+
+/// A proof that the library is initialized, returned by [`init_curve`].
+///
+/// Functions that require an initialized library can take `&Mcl`
+/// to turn the misuse into a compile-time error, e.g.
+/// [`crate::bn::Fr::from_csprng_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mcl {
+    curve: Curve,
+}
+
+impl Mcl {
+    /// Get the handle of the already initialized library.
+    ///
+    /// # Errors
+    /// Returns [`MclError::NotInitialized`] if no curve from [`Curve`] is active.
+    pub fn current() -> Result<Mcl, MclError> {
+        current_curve().map(|curve| Mcl { curve }).ok_or(MclError::NotInitialized)
+    }
+
+    /// The curve the library was initialized with.
+    pub fn curve(&self) -> Curve {
+        self.curve
+    }
 }
 
 /// A high-level wrapper for [`mcl_bn_init`] that chooses appropriate
 /// parameters based on the curve specified in a parameter.
-pub fn init_curve(curve: Curve) {
-    match curve {
-        Curve::Bls12_381 => mcl_bn_init(5, 46),
+///
+/// Calling it again with the same curve is a no-op. Use [`switch_curve`]
+/// to change the curve once the library is initialized.
+///
+/// # Errors
+/// - [`MclError::UnsupportedCurve`] if the linked library can't handle
+///   the curve (see [`Curve`]),
+/// - [`MclError::CurveMismatch`] if another curve is already active,
+/// - [`MclError::Ffi`] if `mclBn_init` fails.
+pub fn init_curve(curve: Curve) -> Result<Mcl, MclError> {
+    if !curve.is_supported() {
+        return Err(MclError::UnsupportedCurve);
+    }
+    // the copy of the id is read without the lock, so a thread holding
+    // a `CurveGuard` isn't blocked by a waiting writer
+    if let Some(id) = active_id() {
+        return if id == curve.id() { Ok(Mcl { curve }) } else { Err(MclError::CurveMismatch) };
+    }
+    let mut active = write_active();
+    match *active {
+        Some(id) if id != curve.id() => Err(MclError::CurveMismatch),
+        _ => ffi_result(init_locked(&mut active, curve.id(), MCLBN_COMPILED_TIME_VAR))
+            .map(|_| Mcl { curve }),
+    }
+}
+
+/// Re-initialize the library with another curve.
+///
+/// To prevent switching the curve in the middle of a computation, the
+/// function waits until all [`CurveGuard`]s are dropped.
+///
+/// # Objects of the previous curve
+/// All of the objects created before the switch become invalid and must not
+/// be used afterwards. This isn't enforced: the [`crate::bn`] types are
+/// plain `Copy` limbs that don't record their curve, so only
+/// [`crate::bn::G2Prepared`] detects that it's stale (and panics), while
+/// the other types silently give meaningless results. Hold a [`CurveGuard`]
+/// as long as the objects are alive to make the switch wait for them.
+///
+/// # Errors
+/// - [`MclError::CurveInUse`] if the calling thread holds a [`CurveGuard`],
+/// - otherwise the same as in [`init_curve`], except for [`MclError::CurveMismatch`].
+pub fn switch_curve(curve: Curve) -> Result<Mcl, MclError> {
+    if !curve.is_supported() {
+        return Err(MclError::UnsupportedCurve);
+    }
+    if is_pinned_by_current_thread() {
+        return Err(MclError::CurveInUse);
+    }
+    let mut active = write_active();
+    ffi_result(init_locked(&mut active, curve.id(), MCLBN_COMPILED_TIME_VAR)).map(|_| Mcl { curve })
+}
+
+/// A non-blocking variant of [`switch_curve`].
+///
+/// Objects of the previous curve aren't detected either, see
+/// [`switch_curve`].
+///
+/// # Errors
+/// Returns [`MclError::CurveInUse`] if any [`CurveGuard`] is alive, otherwise
+/// the same errors as [`switch_curve`].
+pub fn try_switch_curve(curve: Curve) -> Result<Mcl, MclError> {
+    if !curve.is_supported() {
+        return Err(MclError::UnsupportedCurve);
+    }
+    let mut active = match ACTIVE_CURVE.try_write() {
+        Ok(active) => active,
+        Err(TryLockError::Poisoned(e)) => e.into_inner(),
+        Err(TryLockError::WouldBlock) => return Err(MclError::CurveInUse),
     };
+    ffi_result(init_locked(&mut active, curve.id(), MCLBN_COMPILED_TIME_VAR)).map(|_| Mcl { curve })
+}
+
+/// The curve the library is currently initialized with.
+///
+/// Returns `None` if the library isn't initialized or was initialized
+/// with [`mcl_bn_init`] using a curve that isn't a member of [`Curve`].
+pub fn current_curve() -> Option<Curve> {
+    active_id().and_then(Curve::from_id)
+}
+
+/// Keeps the active curve from being switched as long as it's alive.
+///
+/// Hold it for the duration of any computation that must not be
+/// interrupted by [`switch_curve`]. The guards of one thread share
+/// a single read lock, so pinning the same curve again never blocks.
+pub struct CurveGuard {
+    curve: Curve,
+    // the shared read lock is stored in a thread local
+    _not_send: PhantomData<*const ()>,
+}
+
+impl CurveGuard {
+    /// The curve pinned by the guard.
+    pub fn curve(&self) -> Curve {
+        self.curve
+    }
+
+    /// The handle of the library initialized with the pinned curve.
+    pub fn mcl(&self) -> Mcl {
+        Mcl { curve: self.curve }
+    }
+}
+
+impl Drop for CurveGuard {
+    fn drop(&mut self) {
+        // the thread local may already be gone if the guard is dropped
+        // while the thread exits, and then there is nothing to release
+        let _ = PINNED.try_with(|pinned| {
+            let mut pinned = pinned.borrow_mut();
+            if let Some((_, count)) = pinned.as_mut() {
+                *count -= 1;
+                if *count == 0 {
+                    *pinned = None;
+                }
+            }
+        });
+    }
+}
+
+/// Make `curve` the active curve and keep it active until the returned
+/// guard is dropped.
+///
+/// The library is initialized if needed, and if another curve is active it's
+/// switched once all the other guards are released (see [`switch_curve`]).
+///
+/// # Errors
+/// The same as in [`switch_curve`].
+pub fn pin_curve(curve: Curve) -> Result<CurveGuard, MclError> {
+    // taking the read lock again on a thread holding it would deadlock
+    // behind a waiting writer, so the held lock is reused instead
+    let nested = PINNED.with(|pinned| {
+        pinned.borrow_mut().as_mut().map(|(active, count)| match **active == Some(curve.id()) {
+            true => {
+                *count += 1;
+                Ok(CurveGuard { curve, _not_send: PhantomData })
+            }
+            false => Err(MclError::CurveInUse),
+        })
+    });
+    if let Some(result) = nested {
+        return result;
+    }
+    loop {
+        let active = read_active();
+        if *active == Some(curve.id()) {
+            PINNED.with(|pinned| *pinned.borrow_mut() = Some((active, 1)));
+            return Ok(CurveGuard { curve, _not_send: PhantomData });
+        }
+        drop(active);
+        switch_curve(curve)?;
+    }
 }
 
 /// Initialize the MCL library.
@@ -22,12 +298,14 @@ pub fn init_curve(curve: Curve) {
 /// weird and hard to debug memory bugs.
 ///
 /// This function can be safely called by multiple threads and multiple times,
-/// because the current implementation uses [`std::sync::Once`] to assure
-/// the FFI endpoint is called only once.
+/// the FFI endpoint is called only if the library isn't initialized with
+/// `curve` yet. Calling it with another curve re-initializes the library
+/// in the same way as [`switch_curve`] does (see the note about the
+/// objects of the previous curve).
 ///
-/// This also means that at the moment you can't change the elliptic curve using
-/// this method by calling it again. For now, the best solution is to unsafely
-/// call the FFI endpoint [`mclBn_init`] and pass all the necessary parameters.
+/// Returns `-1` without re-initializing if the calling thread holds a
+/// [`CurveGuard`] and `curve` isn't the active one, as [`switch_curve`]
+/// refuses to switch in this case too.
 ///
 /// # Note
 /// You probably shouldn't call this function unless [`init_curve`] doesn't implement
@@ -40,13 +318,187 @@ pub fn init_curve(curve: Curve) {
 /// ```
 ///
 pub fn mcl_bn_init(curve: i32, compiled_time_var: i32) -> i32 {
-    use std::sync::Once;
-    static INIT: Once = Once::new();
-    static mut VAL: i32 = 0;
-    unsafe {
-        INIT.call_once(|| {
-            VAL = mclBn_init(curve, compiled_time_var);
-        });
-        VAL
+    if active_id() == Some(curve) {
+        return 0;
+    }
+    if is_pinned_by_current_thread() {
+        return -1;
+    }
+    init_locked(&mut write_active(), curve, compiled_time_var)
+}
+
+// The id of the curve MCL is initialized with.
+static ACTIVE_CURVE: RwLock<Option<i32>> = RwLock::new(None);
+// A copy of `ACTIVE_CURVE` updated under its write lock, readable without
+// waiting for a pending writer. `NO_CURVE` until the library is initialized.
+static ACTIVE_CURVE_ID: AtomicI32 = AtomicI32::new(NO_CURVE);
+const NO_CURVE: i32 = -1;
+// Incremented every time the curve is switched.
+static CURVE_GENERATION: AtomicUsize = AtomicUsize::new(0);
+// Set once the library is successfully initialized, cheaper to check than `ACTIVE_CURVE`.
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // The read lock shared by the `CurveGuard`s of the thread and their number.
+    static PINNED: RefCell<Option<(RwLockReadGuard<'static, Option<i32>>, usize)>> =
+        const { RefCell::new(None) };
+}
+
+fn active_id() -> Option<i32> {
+    match ACTIVE_CURVE_ID.load(Ordering::SeqCst) {
+        NO_CURVE => None,
+        id => Some(id),
+    }
+}
+
+fn is_pinned_by_current_thread() -> bool {
+    PINNED.with(|pinned| pinned.borrow().is_some())
+}
+
+fn read_active() -> RwLockReadGuard<'static, Option<i32>> {
+    ACTIVE_CURVE.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write_active() -> RwLockWriteGuard<'static, Option<i32>> {
+    ACTIVE_CURVE.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn init_locked(active: &mut Option<i32>, curve: i32, compiled_time_var: i32) -> i32 {
+    if *active == Some(curve) {
+        return 0;
+    }
+    let ret = unsafe { mclBn_init(curve, compiled_time_var) };
+    if ret == 0 {
+        if active.is_some() {
+            CURVE_GENERATION.fetch_add(1, Ordering::SeqCst);
+        }
+        *active = Some(curve);
+        ACTIVE_CURVE_ID.store(curve, Ordering::SeqCst);
+        INITIALIZED.store(true, Ordering::SeqCst);
+        common::reset_map_to_mode();
+    }
+    ret
+}
+
+fn ffi_result(code: i32) -> Result<(), MclError> {
+    match code {
+        0 => Ok(()),
+        code => Err(MclError::Ffi(code)),
+    }
+}
+
+/// Check whether the library has been initialized.
+pub fn is_initialized() -> bool {
+    INITIALIZED.load(Ordering::SeqCst)
+}
+
+pub(crate) fn check_initialized() -> Result<(), MclError> {
+    match is_initialized() {
+        true => Ok(()),
+        false => Err(MclError::NotInitialized),
+    }
+}
+
+/// The number of times the curve has been switched, used to detect
+/// objects that outlived the curve they were created for.
+pub(crate) fn curve_generation() -> usize {
+    CURVE_GENERATION.load(Ordering::SeqCst)
+}
+
+/// Enable or disable the prime-order subgroup check performed by MCL
+/// when deserializing or parsing `G1` points.
+///
+/// The setting is global and affects [`crate::bn::G1::is_valid`] too.
+pub fn verify_order_g1(do_verify: bool) {
+    unsafe { mclBn_verifyOrderG1(do_verify as i32) }
+}
+
+/// Enable or disable the prime-order subgroup check performed by MCL
+/// when deserializing or parsing `G2` points.
+///
+/// The setting is global and affects [`crate::bn::G2::is_valid`] too.
+pub fn verify_order_g2(do_verify: bool) {
+    unsafe { mclBn_verifyOrderG2(do_verify as i32) }
+}
+
+/// Set the global map-to mode used by `hash_and_map` and the `map_to_*`
+/// functions of the `bn` types.
+///
+/// The mode is reset to [`MapToMode::Original`] whenever the curve is
+/// (re-)initialized.
+///
+/// # Errors
+/// Returns [`MclError::Ffi`] if the mode isn't supported for the current curve.
+pub fn set_map_to_mode(mode: MapToMode) -> Result<(), MclError> {
+    common::set_map_to_mode(mode)
+}
+
+/// The current global map-to mode.
+pub fn map_to_mode() -> MapToMode {
+    common::map_to_mode()
+}
+
+/// Make the CSPRNG functions of all types (e.g. `Fr::from_csprng`) read
+/// their randomness from `rng` instead of the CSPRNG of MCL.
+pub fn set_rng_source(rng: impl RngSource + Send + 'static) {
+    common::set_rng_source(Some(Box::new(rng)))
+}
+
+/// Restore the CSPRNG of MCL replaced by [`set_rng_source`].
+pub fn reset_rng_source() {
+    common::set_rng_source(None)
+}
+
+/// Set the global serialization mode used by
+/// [`crate::traits::RawSerializable::serialize_raw`] and the serde impls.
+///
+/// When `enable` is `true` the Ethereum/ZCash format is used, otherwise
+/// the native MCL one. Prefer [`crate::traits::RawSerializable::serialize_with`]
+/// if only a part of the program needs a different format.
+pub fn set_eth_serialization(enable: bool) {
+    common::set_eth_serialization(enable)
+}
+
+/// Check whether the global serialization mode is the Ethereum/ZCash one.
+pub fn eth_serialization() -> bool {
+    with_serialization_mode(None, || unsafe { mclBn_getETHserialization() == 1 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsupported_curves() {
+        assert_eq!(init_curve(Curve::Bn381_1), Err(MclError::UnsupportedCurve));
+        assert_eq!(init_curve(Curve::Bn462), Err(MclError::UnsupportedCurve));
+        assert_eq!(init_curve(Curve::Bls12_461), Err(MclError::UnsupportedCurve));
+        assert!(Curve::Bn254.is_supported());
+        assert!(Curve::Bls12_377.is_supported());
+        assert!(Curve::Bls12_381.is_supported());
+    }
+
+    #[test]
+    fn test_switch_curve() {
+        let guard = pin_curve(Curve::Bn254).unwrap();
+        assert_eq!(current_curve(), Some(Curve::Bn254));
+        assert_eq!(guard.curve(), Curve::Bn254);
+        assert_eq!(init_curve(Curve::Bn254).map(|mcl| mcl.curve()), Ok(Curve::Bn254));
+        assert_eq!(Mcl::current(), Ok(guard.mcl()));
+        assert_eq!(init_curve(Curve::Bls12_381), Err(MclError::CurveMismatch));
+        assert_eq!(try_switch_curve(Curve::Bls12_381), Err(MclError::CurveInUse));
+        assert_eq!(switch_curve(Curve::Bls12_381), Err(MclError::CurveInUse));
+        assert_eq!(pin_curve(Curve::Bls12_381).err(), Some(MclError::CurveInUse));
+        assert_eq!(mcl_bn_init(Curve::Bls12_381.id(), MCLBN_COMPILED_TIME_VAR), -1);
+        assert_eq!(mcl_bn_init(Curve::Bn254.id(), MCLBN_COMPILED_TIME_VAR), 0);
+        let nested = pin_curve(Curve::Bn254).unwrap();
+        drop(guard);
+        assert_eq!(try_switch_curve(Curve::Bls12_381), Err(MclError::CurveInUse));
+        drop(nested);
+
+        let generation = curve_generation();
+        let _guard = pin_curve(Curve::Bls12_381).unwrap();
+        assert_eq!(current_curve(), Some(Curve::Bls12_381));
+        assert!(curve_generation() > generation);
     }
 }
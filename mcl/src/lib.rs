@@ -1,5 +1,12 @@
 pub mod common;
+pub mod error;
 pub mod traits;
 pub mod ffi;
 pub mod init;
 pub mod bn;
+pub mod hash_to_curve;
+pub mod parallel;
+pub mod rng;
+pub mod secret;
+#[cfg(feature = "serde_lib")]
+pub mod serde_format;
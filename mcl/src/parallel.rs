@@ -0,0 +1,149 @@
+//! Helpers spreading batch operations over all cores with [`std::thread::scope`].
+//!
+//! # Thread safety
+//! All of the [`crate::bn`] types are plain arrays of limbs that MCL only
+//! reads through `const` pointers, so they are `Send` and `Sync`, and the
+//! arithmetic functions can be called concurrently from any thread.
+//!
+//! The global state of MCL is shared by all threads:
+//! - the active curve, set by [`crate::init`] under a lock. Hold a
+//!   [`crate::init::CurveGuard`] while computing if other threads may
+//!   switch the curve;
+//! - the serialization and map-to modes, read under a shared lock and
+//!   changed only under an exclusive one by the wrappers of this crate;
+//! - the flags of [`crate::init::verify_order_g1`] and
+//!   [`crate::init::verify_order_g2`], which should be set once at startup;
+//! - the random source of MCL. Sampling (e.g. `Fr::from_csprng`) is
+//!   serialized by a global mutex, so the threads don't sample concurrently.
+
+use crate::bn::{Fr, G1, G2, GT};
+use crate::error::MclError;
+
+use std::num::NonZeroUsize;
+use std::ops::Mul;
+use std::thread;
+
+// Compile-time check of the guarantees documented above.
+#[allow(dead_code)]
+fn assert_send_sync() {
+    fn check<T: Send + Sync>() {}
+    check::<crate::bn::Fp>();
+    check::<crate::bn::Fp2>();
+    check::<Fr>();
+    check::<G1>();
+    check::<G2>();
+    check::<GT>();
+    check::<crate::bn::G2Prepared>();
+}
+
+// Split `len` items evenly between the available cores.
+fn chunk_size(len: usize) -> usize {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    len.div_ceil(threads).max(1)
+}
+
+/// Compute `prod e(p_i, q_i)` like [`GT::multi_pairing`], running the
+/// Miller loops of the pairs on all cores.
+pub fn multi_pairing(pairs: &[(G1, G2)]) -> GT {
+    thread::scope(|s| {
+        let handles: Vec<_> = pairs
+            .chunks(chunk_size(pairs.len()))
+            .map(|chunk| {
+                s.spawn(move || {
+                    let (ps, qs): (Vec<G1>, Vec<G2>) = chunk.iter().cloned().unzip();
+                    GT::miller_loop_vec(&ps, &qs).expect("unzipped slices have equal lengths")
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("Miller loop thread panicked"))
+            .product::<GT>()
+    })
+    .final_exp()
+}
+
+/// Check whether `prod e(p_i, q_i)` equals one using [`multi_pairing`].
+pub fn pairing_check(pairs: &[(G1, G2)]) -> bool {
+    multi_pairing(pairs).is_one()
+}
+
+/// Compute `points[i] * scalars[i]` for all `i` on all cores.
+///
+/// # Errors
+/// Returns [`MclError::LengthMismatch`] if the slices have different lengths.
+pub fn batch_mul<P>(points: &[P], scalars: &[Fr]) -> Result<Vec<P>, MclError>
+where
+    P: Send + Sync,
+    for<'a> &'a P: Mul<&'a Fr, Output = P>,
+{
+    if points.len() != scalars.len() {
+        return Err(MclError::LengthMismatch);
+    }
+    let size = chunk_size(points.len());
+    Ok(thread::scope(|s| {
+        let handles: Vec<_> = points
+            .chunks(size)
+            .zip(scalars.chunks(size))
+            .map(|(points, scalars)| {
+                s.spawn(move || {
+                    points.iter().zip(scalars).map(|(p, x)| p * x).collect::<Vec<P>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("multiplication thread panicked"))
+            .collect()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init;
+
+    #[test]
+    fn test_parallel_helpers() {
+        let _guard = init::pin_curve(init::Curve::Bls12_381).unwrap();
+        let scalars: Vec<Fr> = (0..33).map(|_| Fr::from_csprng()).collect();
+        let g = G1::hash_and_map(b"g").unwrap();
+        let h = G2::hash_and_map(b"h").unwrap();
+        let points = vec![g.clone(); scalars.len()];
+
+        let products = batch_mul(&points, &scalars).unwrap();
+        let expected: Vec<G1> = scalars.iter().map(|x| &g * x).collect();
+        assert_eq!(products, expected);
+        assert_eq!(batch_mul(&points, &scalars[1..]), Err(MclError::LengthMismatch));
+        assert!(batch_mul::<G2>(&[], &[]).unwrap().is_empty());
+
+        let pairs: Vec<(G1, G2)> = products.into_iter().map(|p| (p, h.clone())).collect();
+        assert_eq!(multi_pairing(&pairs), GT::multi_pairing(&pairs));
+        assert!(multi_pairing(&[]).is_one());
+
+        // e(xG, H) * e(-G, xH) == 1
+        let x = scalars[0];
+        assert!(pairing_check(&[(&g * x, h.clone()), (g.neg(), &h * x)]));
+    }
+
+    #[test]
+    fn test_concurrent_use() {
+        let _guard = init::pin_curve(init::Curve::Bls12_381).unwrap();
+        let g = G1::hash_and_map(b"g").unwrap();
+        let h = G2::hash_and_map(b"h").unwrap();
+        let expected = GT::from_pairing(&g, &h);
+
+        thread::scope(|s| {
+            for _ in 0..16 {
+                s.spawn(|| {
+                    for _ in 0..8 {
+                        let x = Fr::from_csprng();
+                        let e = GT::from_pairing(&(&g * x), &h);
+                        assert_eq!(e, expected.pow(&x));
+                        assert!(pairing_check(&[(&g * x, h.clone()), (g.neg(), &h * x)]));
+                    }
+                });
+            }
+        });
+    }
+}
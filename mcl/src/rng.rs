@@ -0,0 +1,77 @@
+//! Random sources for [`crate::traits::Random::set_by_rng`] and
+//! [`crate::init::set_rng_source`].
+
+use crate::traits::RngSource;
+
+use sha2::{Digest, Sha256};
+
+/// A seedable generator expanding the seed with SHA-256 in counter mode.
+///
+/// Meant for reproducible tests, don't use it to generate secrets.
+#[derive(Debug, Clone)]
+pub struct DeterministicRng {
+    seed: [u8; 32],
+    counter: u64,
+    block: [u8; 32],
+    used: usize,
+}
+
+impl DeterministicRng {
+    pub fn from_seed(seed: &[u8]) -> DeterministicRng {
+        DeterministicRng {
+            seed: Sha256::digest(seed).into(),
+            counter: 0,
+            block: [0u8; 32],
+            used: 32,
+        }
+    }
+}
+
+impl RngSource for DeterministicRng {
+    fn fill_bytes(&mut self, buf: &mut [u8]) {
+        for byte in buf.iter_mut() {
+            if self.used == self.block.len() {
+                self.block = Sha256::new()
+                    .chain_update(self.seed)
+                    .chain_update(self.counter.to_le_bytes())
+                    .finalize()
+                    .into();
+                self.counter += 1;
+                self.used = 0;
+            }
+            *byte = self.block[self.used];
+            self.used += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn::{Fp, Fp2, Fr};
+    use crate::init;
+
+    #[test]
+    fn test_deterministic_rng() {
+        let mut a = DeterministicRng::from_seed(b"seed");
+        let mut b = DeterministicRng::from_seed(b"seed");
+        let (mut x, mut y) = ([0u8; 45], [0u8; 45]);
+        a.fill_bytes(&mut x[..13]);
+        a.fill_bytes(&mut x[13..]);
+        b.fill_bytes(&mut y);
+        assert_eq!(x, y);
+        DeterministicRng::from_seed(b"other").fill_bytes(&mut y);
+        assert_ne!(x, y);
+    }
+
+    #[test]
+    fn test_from_rng() {
+        let _guard = init::pin_curve(init::Curve::Bls12_381).unwrap();
+        let mut a = DeterministicRng::from_seed(b"seed");
+        let mut b = a.clone();
+        assert_eq!(Fr::from_rng(&mut a), Fr::from_rng(&mut b));
+        assert_eq!(Fp::from_rng(&mut a), Fp::from_rng(&mut b));
+        assert_eq!(Fp2::from_rng(&mut a), Fp2::from_rng(&mut b));
+        assert_ne!(Fr::from_rng(&mut a), Fr::from_rng(&mut a));
+    }
+}
@@ -0,0 +1,144 @@
+//! A wrapper for secret scalars, e.g. private keys.
+
+use crate::bn::Fr;
+use crate::error::MclError;
+use crate::traits::RawSerializable;
+
+use std::convert::TryFrom;
+use std::fmt;
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// A secret `Fr`, wiped from memory when dropped.
+///
+/// Unlike `Fr` it isn't `Copy`, its `Debug` and `Display` don't reveal
+/// the value and equality is checked in constant time. The value is
+/// only reachable through [`SecretFr::expose_secret`], and serialized
+/// through [`SecretFr::serialize_secret`].
+///
+/// Multiplying a `G1` or `G2` point by a `&SecretFr` uses the
+/// constant-time multiplication of MCL, and `GT::pow_secret` the
+/// constant-time exponentiation.
+///
+/// Copies of the value made by the arithmetic operators of `Fr` aren't
+/// wiped, keep the exposed references short-lived.
+pub struct SecretFr {
+    inner: Fr,
+}
+
+impl SecretFr {
+    /// Move the value out of `value` into a secret, wiping `value`.
+    pub fn new(value: &mut Fr) -> SecretFr {
+        let secret = SecretFr { inner: *value };
+        value.zeroize();
+        secret
+    }
+
+    /// Sample a uniformly random secret using the CSPRNG of MCL.
+    ///
+    /// # Panics
+    /// Panics if the library isn't initialized.
+    pub fn from_csprng() -> SecretFr {
+        SecretFr { inner: Fr::from_csprng() }
+    }
+
+    /// Access the secret value.
+    pub fn expose_secret(&self) -> &Fr {
+        &self.inner
+    }
+
+    /// Serialize the secret value as [`RawSerializable::serialize_raw`] does.
+    ///
+    /// The returned bytes are wiped when dropped.
+    pub fn serialize_secret(&self) -> Result<Zeroizing<Vec<u8>>, MclError> {
+        self.inner.serialize_raw().map(Zeroizing::new)
+    }
+
+    /// Deserialize a secret value serialized with [`SecretFr::serialize_secret`].
+    ///
+    /// # Errors
+    /// Returns [`MclError::InvalidEncoding`] unless `bytes` hold exactly one `Fr`.
+    pub fn deserialize_secret(bytes: &[u8]) -> Result<SecretFr, MclError> {
+        Fr::try_from(bytes).map(|mut value| SecretFr::new(&mut value))
+    }
+}
+
+impl From<Fr> for SecretFr {
+    /// Wrap `value`. Only the moved copy is wiped, use [`SecretFr::new`]
+    /// to wipe the original.
+    fn from(mut value: Fr) -> SecretFr {
+        SecretFr::new(&mut value)
+    }
+}
+
+impl Clone for SecretFr {
+    fn clone(&self) -> SecretFr {
+        SecretFr { inner: self.inner }
+    }
+}
+
+impl Drop for SecretFr {
+    fn drop(&mut self) {
+        self.inner.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretFr {}
+
+impl ConstantTimeEq for SecretFr {
+    fn ct_eq(&self, other: &SecretFr) -> Choice {
+        self.inner.ct_eq(&other.inner)
+    }
+}
+
+impl PartialEq for SecretFr {
+    fn eq(&self, other: &SecretFr) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for SecretFr {}
+
+impl fmt::Debug for SecretFr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SecretFr(<redacted>)")
+    }
+}
+
+impl fmt::Display for SecretFr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bn::{G1, G2, GT};
+    use crate::init;
+
+    #[test]
+    fn test_secret_fr() {
+        let _guard = init::pin_curve(init::Curve::Bls12_381).unwrap();
+        let value = Fr::from_csprng();
+        let mut copy = value;
+        let secret = SecretFr::new(&mut copy);
+        assert!(copy.is_zero());
+        assert_eq!(*secret.expose_secret(), value);
+        assert_eq!(format!("{:?}", secret), "SecretFr(<redacted>)");
+        assert_eq!(secret.to_string(), "<redacted>");
+
+        let bytes = secret.serialize_secret().unwrap();
+        assert_eq!(SecretFr::deserialize_secret(&bytes).unwrap(), secret);
+        assert_eq!(secret.clone(), secret);
+        assert_ne!(SecretFr::from_csprng(), secret);
+
+        let g = G1::hash_and_map(b"g").unwrap();
+        let h = G2::hash_and_map(b"h").unwrap();
+        assert_eq!(&g * &secret, &g * value);
+        assert_eq!(h.clone() * &secret, h.mul_ct(&value));
+        let e = GT::from_pairing(&g, &h);
+        assert_eq!(e.pow_ct(&value), e.pow(&value));
+        assert_eq!(e.pow_secret(&secret), e.pow(&value));
+    }
+}
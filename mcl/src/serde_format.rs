@@ -0,0 +1,96 @@
+//! Modules for `#[serde(with = "...")]` that serialize MCL objects
+//! in an explicit [`SerializationFormat`] instead of the global one.
+//!
+//! # Examples
+//! ```ignore
+//! use mcl::bn::G1;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct PublicKey {
+//!     #[serde(with = "mcl::serde_format::eth")]
+//!     point: G1,
+//! }
+//! ```
+
+use crate::{common::SerializationFormat, traits::RawSerializable};
+use serde::{de, Deserializer, Serializer};
+use std::{fmt, marker::PhantomData};
+
+fn serialize<T, S>(value: &T, serializer: S, format: SerializationFormat) -> Result<S::Ok, S::Error>
+where
+    T: RawSerializable,
+    S: Serializer,
+{
+    use serde::ser::Error;
+    let buf = value.serialize_with(format).map_err(S::Error::custom)?;
+    serializer.serialize_bytes(&buf)
+}
+
+struct FormatVisitor<T> {
+    format: SerializationFormat,
+    marker: PhantomData<T>,
+}
+
+impl<'de, T: RawSerializable + Default> de::Visitor<'de> for FormatVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "bytes serializing the mcl object in {:?} format", self.format)
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let mut val = T::default();
+        val.deserialize_with(bytes, self.format).map_err(E::custom)?;
+        Ok(val)
+    }
+}
+
+fn deserialize<'de, T, D>(deserializer: D, format: SerializationFormat) -> Result<T, D::Error>
+where
+    T: RawSerializable + Default,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_bytes(FormatVisitor { format, marker: PhantomData })
+}
+
+macro_rules! format_module {
+    ($(#[$doc:meta])* $module:ident, $format:expr) => {
+        $(#[$doc])*
+        pub mod $module {
+            use super::*;
+
+            pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                T: RawSerializable,
+                S: Serializer,
+            {
+                super::serialize(value, serializer, $format)
+            }
+
+            pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+            where
+                T: RawSerializable + Default,
+                D: Deserializer<'de>,
+            {
+                super::deserialize(deserializer, $format)
+            }
+        }
+    };
+}
+
+format_module!(
+    /// Serialize using [`SerializationFormat::Mcl`].
+    mcl, SerializationFormat::Mcl
+);
+format_module!(
+    /// Serialize using [`SerializationFormat::Eth`].
+    eth, SerializationFormat::Eth
+);
+format_module!(
+    /// Serialize using [`SerializationFormat::EthUncompressed`].
+    eth_uncompressed, SerializationFormat::EthUncompressed
+);
@@ -1,4 +1,4 @@
-use crate::common::Base;
+use crate::{common::{IoMode, SerializationFormat}, error::MclError};
 
 /// An interface for using the custom MCL serialization format.
 /// The result types are used to wrap the result type of
@@ -8,7 +8,8 @@ use crate::common::Base;
 ///
 /// When the feature `serde_lib` is enabled, then this trait MCL objects
 /// deriving from this trait using the `RawSerializable` macro also implement
-/// [`serde::Serialize`] and [`serde::Deserialize`].
+/// [`serde::Serialize`] and [`serde::Deserialize`]. Use the modules from
+/// [`crate::serde_format`] to serialize with an explicit format.
 pub trait RawSerializable {
     /// Serialize the object into a vector of bytes.
     ///
@@ -17,31 +18,81 @@ pub trait RawSerializable {
     /// manipulated.
     ///
     /// # Errors
-    /// Returns `Err(())` when the `ffi` serialization function returns
-    /// `0` corresponding to the number of bytes copied.
+    /// Returns [`MclError::BufferTooSmall`] when the `ffi` serialization
+    /// function returns `0` corresponding to the number of bytes copied.
     ///
-    fn serialize_raw(&self) -> Result<Vec<u8>, ()>;
+    fn serialize_raw(&self) -> Result<Vec<u8>, MclError>;
     /// Deserialize the object from a vector of bytes in place.
     ///
     /// If ok, this function returns the number of bytes consumed.
     ///
     /// # Errors
-    /// Returns `Err(())` when the `ffi` deserialization function returns
-    /// `0` corresponding to the number of bytes copied.
+    /// Returns [`MclError::InvalidEncoding`] when the `ffi` deserialization
+    /// function returns `0` corresponding to the number of bytes copied.
     ///
     /// # Safety
     /// This function is fairly safe, trying to deserialize an empty vector
     /// doesn't trigger an UB and generally the degenerate cases yield
     /// `Error`s as they should.
-    fn deserialize_raw(&mut self, bytes: &[u8]) -> Result<usize, ()>;
+    fn deserialize_raw(&mut self, bytes: &[u8]) -> Result<usize, MclError>;
+    /// Serialize the object using an explicit format, regardless of the
+    /// global mode set by [`crate::init::set_eth_serialization`].
+    ///
+    /// # Errors
+    /// The same as in [`RawSerializable::serialize_raw`].
+    fn serialize_with(&self, format: SerializationFormat) -> Result<Vec<u8>, MclError>;
+    /// Deserialize the object in place using an explicit format, regardless
+    /// of the global mode set by [`crate::init::set_eth_serialization`].
+    ///
+    /// # Errors
+    /// The same as in [`RawSerializable::deserialize_raw`].
+    fn deserialize_with(
+        &mut self,
+        bytes: &[u8],
+        format: SerializationFormat,
+    ) -> Result<usize, MclError>;
 }
 
+/// An interface for the string representation of MCL objects,
+/// wrapping `mclBn<name>_setStr` and `mclBn<name>_getStr`.
 pub trait Formattable {
-    fn set_str(&mut self, buffer: &str, io_mode: Base);
-    fn get_str(&self, io_mode: Base) -> String;
+    /// Set the value of the object from its string representation.
+    ///
+    /// # Errors
+    /// Returns [`MclError::InvalidEncoding`] when MCL can't parse `buffer`.
+    fn set_str(&mut self, buffer: &str, io_mode: IoMode) -> Result<(), MclError>;
+    /// Get the string representation of the object.
+    ///
+    /// # Errors
+    /// Returns [`MclError::BufferTooSmall`] when the representation
+    /// doesn't fit into the internal buffer and [`MclError::InvalidEncoding`]
+    /// when `io_mode` yields bytes that aren't valid UTF-8 (e.g. [`IoMode::SERIALIZE`]).
+    fn get_str(&self, io_mode: IoMode) -> Result<String, MclError>;
 }
 
 pub trait Random {
+    /// Set the value to a uniformly random one using the CSPRNG of MCL,
+    /// or the source installed with [`crate::init::set_rng_source`].
+    ///
+    /// # Panics
+    /// Panics if the library isn't initialized or the random source fails.
     fn set_by_csprng(&mut self);
+    /// Set the value to a uniformly random one sampled from the bytes of `rng`.
+    ///
+    /// # Panics
+    /// Panics if the library isn't initialized or `rng` panics.
+    fn set_by_rng<R: RngSource>(&mut self, rng: &mut R);
+}
+
+/// A source of random bytes replacing the CSPRNG of MCL.
+pub trait RngSource {
+    /// Fill `buf` with random bytes.
+    fn fill_bytes(&mut self, buf: &mut [u8]);
+}
+
+impl<R: RngSource + ?Sized> RngSource for Box<R> {
+    fn fill_bytes(&mut self, buf: &mut [u8]) {
+        (**self).fill_bytes(buf)
+    }
 }
 
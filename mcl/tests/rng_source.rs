@@ -0,0 +1,24 @@
+// Runs in its own process, so no other test samples from the global
+// random source while it's replaced.
+
+use mcl::{
+    bn::*,
+    init::{self, Curve},
+    rng::DeterministicRng,
+};
+
+#[test]
+fn test_global_rng_source() {
+    init::init_curve(Curve::Bls12_381).unwrap();
+
+    init::set_rng_source(DeterministicRng::from_seed(b"global"));
+    let x = Fr::from_csprng();
+    let y = G1::hash_and_map(b"g").unwrap() * Fr::from_csprng();
+    init::set_rng_source(DeterministicRng::from_seed(b"global"));
+    assert_eq!(Fr::from_csprng(), x);
+    assert_eq!(G1::hash_and_map(b"g").unwrap() * Fr::from_csprng(), y);
+    assert_eq!(Fr::from_rng(&mut DeterministicRng::from_seed(b"global")), x);
+
+    init::reset_rng_source();
+    assert_ne!(Fr::from_csprng(), x);
+}
@@ -0,0 +1,36 @@
+// Runs in its own process, so the library is guaranteed to be uninitialized
+// at the beginning of the test.
+
+use mcl::{
+    bn::*,
+    common::IoMode,
+    error::MclError,
+    init::{self, Curve, Mcl},
+    traits::*,
+};
+use std::convert::TryFrom;
+
+#[test]
+fn test_constructors_before_init() {
+    assert!(!init::is_initialized());
+    assert_eq!(Mcl::current(), Err(MclError::NotInitialized));
+    assert_eq!(Fr::from_str("1", IoMode::DEC), Err(MclError::NotInitialized));
+    assert_eq!("1".parse::<Fr>(), Err(MclError::NotInitialized));
+    assert_eq!(G1::hash_and_map(b"abc"), Err(MclError::NotInitialized));
+    assert_eq!(G2::try_from(&[0u8; 96][..]), Err(MclError::NotInitialized));
+    assert_eq!(GT::default().deserialize_raw(&[1]), Err(MclError::NotInitialized));
+    assert!(std::panic::catch_unwind(Fr::from_csprng).is_err());
+    assert_eq!(Fr::try_from_csprng(), Err(MclError::NotInitialized));
+    assert_eq!(Fp::try_one(), Err(MclError::NotInitialized));
+    assert_eq!(Fr::try_from_i64(-1), Err(MclError::NotInitialized));
+    assert_eq!(Fp::try_from_u64(1), Err(MclError::NotInitialized));
+    assert_eq!(G2Prepared::new(&G2::default()), Err(MclError::NotInitialized));
+
+    let mcl = init::init_curve(Curve::Bls12_381).unwrap();
+    assert_eq!(mcl.curve(), Curve::Bls12_381);
+    assert!(init::is_initialized());
+    assert!(G1::hash_and_map(b"abc").is_ok());
+    assert_ne!(Fr::from_csprng_with(&mcl), Fr::try_from_csprng().unwrap());
+    assert_eq!(Fr::try_one(), Ok(Fr::one()));
+    assert_eq!(Fr::try_from_i64(-1), Ok(-Fr::one()));
+}
@@ -40,6 +40,88 @@ macro_rules! forward_ref_binop {
     };
 }
 
+// implements "T op= U" and "T op= &U" by calling the MCL function `$ffi`
+// in place, MCL allows the output to alias the inputs
+macro_rules! assign_op {
+    (impl $imp:ident, $method:ident for $t:ident, $u:ident, $ffi:ident) => {
+        TokenStream::from(quote! {
+            impl $imp<#$u> for #$t {
+                #[inline]
+                fn $method(&mut self, other: #$u) {
+                    $imp::$method(self, &other)
+                }
+            }
+
+            impl<'a> $imp<&'a #$u> for #$t {
+                #[inline]
+                fn $method(&mut self, other: &'a #$u) {
+                    let z = &mut self.inner as *mut _;
+                    unsafe {
+                        #$ffi(z, z as *const _, &other.inner);
+                    }
+                }
+            }
+        })
+    };
+}
+
+// implements `Neg` for "T" in place and for "&T"
+macro_rules! neg_op {
+    ($t:ident, $ffi:ident) => {
+        TokenStream::from(quote! {
+            // `Neg` is spelled out so that the trait doesn't shadow the
+            // inherent `neg(&self)` when imported
+            impl std::ops::Neg for #$t {
+                type Output = #$t;
+
+                #[inline]
+                fn neg(mut self) -> #$t {
+                    let z = &mut self.inner as *mut _;
+                    unsafe {
+                        #$ffi(z, z as *const _);
+                    }
+                    self
+                }
+            }
+
+            impl<'a> std::ops::Neg for &'a #$t {
+                type Output = #$t;
+
+                #[inline]
+                fn neg(self) -> #$t {
+                    #$t::neg(self)
+                }
+            }
+        })
+    };
+}
+
+// implements `Sum` or `Product` for "T" and "&T" by folding with the
+// assignment operator `$assign` starting from `T::$start()`
+macro_rules! fold_op {
+    (impl $imp:ident, $method:ident for $t:ident, $start:ident, $assign:ident, $assign_method:ident) => {
+        TokenStream::from(quote! {
+            impl $imp for #$t {
+                fn $method<I: Iterator<Item = #$t>>(iter: I) -> #$t {
+                    iter.fold(#$t::$start(), |mut acc, x| {
+                        $assign::$assign_method(&mut acc, &x);
+                        acc
+                    })
+                }
+            }
+
+            impl<'a> $imp<&'a #$t> for #$t {
+                fn $method<I: Iterator<Item = &'a #$t>>(iter: I) -> #$t {
+                    iter.fold(#$t::$start(), |mut acc, x| {
+                        $assign::$assign_method(&mut acc, x);
+                        acc
+                    })
+                }
+            }
+        })
+    };
+}
+
 macro_rules! ident {
     ($pat: literal, $name: ident) => {
         Ident::new(&format!($pat, $name), Span::call_site())
@@ -51,34 +133,38 @@ pub fn derive_formattable(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let name = ast.ident;
 
+    let inner_t = ident!("MclBn{}", name);
+
     let set_str_fn = ident!("mclBn{}_setStr", name);
     let get_str_fn = ident!("mclBn{}_getStr", name);
 
-    let inner_t = ident!("MclBn{}", name);
-
     let expanded = quote! {
         impl #name {
-            pub fn from_str(buffer: &str, io_mode: Base) -> Self {
+            pub fn from_str(buffer: &str, io_mode: IoMode) -> Result<Self, MclError> {
                 let mut result = Self::default();
-                result.set_str(buffer, io_mode);
-                result
+                result.set_str(buffer, io_mode)?;
+                Ok(result)
             }
-        }
 
-        impl Formattable for #name {
-            fn set_str(&mut self, buffer: &str, io_mode: Base) {
+            // The serialization modes depend on the global ETH flag, so both
+            // functions must be called under the serialization lock.
+            fn set_str_unlocked(&mut self, buffer: &str, io_mode: IoMode) -> Result<(), MclError> {
+                crate::init::check_initialized()?;
                 let err = unsafe {
                     #set_str_fn(
                         &mut self.inner as *mut #inner_t,
                         buffer.as_ptr() as *const std::os::raw::c_char,
                         buffer.len() as libc::size_t,
-                        io_mode as libc::c_int,
+                        io_mode.bits(),
                     )
                 };
-                assert_eq!(err, 0);
+                match err {
+                    0 => Ok(()),
+                    _ => Err(MclError::InvalidEncoding),
+                }
             }
 
-            fn get_str(&self, io_mode: Base) -> String {
+            fn get_str_unlocked(&self, io_mode: IoMode) -> Result<String, MclError> {
                 let len = 2048;
                 let mut buf = vec![0u8; len];
                 let bytes = unsafe {
@@ -86,20 +172,74 @@ pub fn derive_formattable(input: TokenStream) -> TokenStream {
                         buf.as_mut_ptr() as *mut std::os::raw::c_char,
                         len as libc::size_t,
                         &self.inner as *const #inner_t,
-                        io_mode as libc::c_int,
+                        io_mode.bits(),
                     )
                 };
-                assert_ne!(bytes, 0);
-                String::from_utf8_lossy(&buf[..bytes]).into_owned()
+                buf.truncate(bytes);
+                match bytes {
+                    0 => Err(MclError::BufferTooSmall),
+                    _ => String::from_utf8(buf).map_err(|_| MclError::InvalidEncoding),
+                }
             }
+        }
+
+        impl Formattable for #name {
+            fn set_str(&mut self, buffer: &str, io_mode: IoMode) -> Result<(), MclError> {
+                crate::common::with_serialization_mode(None, || self.set_str_unlocked(buffer, io_mode))
+            }
+
+            fn get_str(&self, io_mode: IoMode) -> Result<String, MclError> {
+                crate::common::with_serialization_mode(None, || self.get_str_unlocked(io_mode))
+            }
+        }
+
+        impl std::str::FromStr for #name {
+            type Err = MclError;
+
+            /// Parse the decimal representation, or the hexadecimal one
+            /// if the string starts with `0x`.
+            fn from_str(s: &str) -> Result<Self, MclError> {
+                match s.strip_prefix("0x") {
+                    Some(hex) => #name::from_str(hex, IoMode::HEX),
+                    None => #name::from_str(s, IoMode::DEC),
+                }
+            }
+        }
 
+        impl std::fmt::Display for #name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                let s = self.get_str(IoMode::DEC).map_err(|_| std::fmt::Error)?;
+                f.write_str(&s)
+            }
+        }
+
+        impl std::fmt::LowerHex for #name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                let s = self.get_str(IoMode::HEX).map_err(|_| std::fmt::Error)?;
+                if f.alternate() {
+                    f.write_str("0x")?;
+                }
+                f.write_str(&s)
+            }
+        }
+
+        impl std::fmt::UpperHex for #name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                let s = self.get_str(IoMode::HEX).map_err(|_| std::fmt::Error)?;
+                if f.alternate() {
+                    f.write_str("0x")?;
+                }
+                f.write_str(&s.to_uppercase())
+            }
         }
     };
 
     TokenStream::from(expanded)
 }
 
-#[proc_macro_derive(Random)]
+// `#[custom_fill]` marks the types without a `setByCSPRNG` function of MCL,
+// they implement `fn fill_by_csprng(&mut self) -> bool` by hand instead.
+#[proc_macro_derive(Random, attributes(custom_fill))]
 pub fn derive_from_csprng(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let name = ast.ident;
@@ -107,26 +247,330 @@ pub fn derive_from_csprng(input: TokenStream) -> TokenStream {
     let inner_t = ident!("MclBn{}", name);
     let from_csprng_fn = ident!("mclBn{}_setByCSPRNG", name);
 
+    let custom_fill = ast.attrs.iter().any(|attr| attr.path.is_ident("custom_fill"));
+    let fill_by_csprng = if custom_fill {
+        quote! {}
+    } else {
+        quote! {
+            impl #name {
+                // Must be called with the random source locked.
+                fn fill_by_csprng(&mut self) -> bool {
+                    unsafe { #from_csprng_fn(&mut self.inner as *mut #inner_t) == 0 }
+                }
+            }
+        }
+    };
+
     let expanded = quote! {
+        #fill_by_csprng
+
         impl Random for #name {
             fn set_by_csprng(&mut self) {
-                unsafe { #from_csprng_fn(&mut self.inner as *mut #inner_t) };
+                assert!(crate::init::is_initialized(), "MCL library is not initialized");
+                let ok = crate::common::with_rng_lock(|| self.fill_by_csprng());
+                assert!(ok, "the random source failed");
+            }
+
+            fn set_by_rng<R: RngSource>(&mut self, rng: &mut R) {
+                assert!(crate::init::is_initialized(), "MCL library is not initialized");
+                let ok = crate::common::with_rng(rng, || self.fill_by_csprng());
+                assert!(ok, "the random source panicked");
             }
         }
 
         impl #name {
+            /// Sample a uniformly random value using the CSPRNG of MCL.
+            ///
+            /// # Panics
+            /// Panics if the library isn't initialized.
             pub fn from_csprng() -> Self {
                 let mut result = #name::default();
                 result.set_by_csprng();
                 result
             }
+
+            /// A fallible variant of [`Self::from_csprng`].
+            ///
+            /// # Errors
+            /// - [`MclError::NotInitialized`] if the library isn't initialized,
+            /// - [`MclError::RandomSource`] if the random source fails.
+            pub fn try_from_csprng() -> Result<Self, MclError> {
+                crate::init::check_initialized()?;
+                let mut result = #name::default();
+                match crate::common::with_rng_lock(|| result.fill_by_csprng()) {
+                    true => Ok(result),
+                    false => Err(MclError::RandomSource),
+                }
+            }
+
+            /// A variant of [`Self::from_csprng`] taking the proof that the
+            /// library is initialized.
+            ///
+            /// # Panics
+            /// Panics if the random source fails.
+            pub fn from_csprng_with(_mcl: &crate::init::Mcl) -> Self {
+                let mut result = #name::default();
+                let ok = crate::common::with_rng_lock(|| result.fill_by_csprng());
+                assert!(ok, "the random source failed");
+                result
+            }
+
+            /// Sample a uniformly random value from the bytes of `rng`.
+            ///
+            /// # Panics
+            /// Panics if the library isn't initialized or `rng` panics.
+            pub fn from_rng<R: RngSource>(rng: &mut R) -> Self {
+                let mut result = #name::default();
+                result.set_by_rng(rng);
+                result
+            }
         }
     };
 
     TokenStream::from(expanded)
 }
 
-#[proc_macro_derive(Object)]
+#[proc_macro_derive(PrimeField)]
+pub fn derive_prime_field(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let name = ast.ident;
+
+    let byte_size_fn = ident!("mclBn_get{}ByteSize", name);
+    let set_int_fn = ident!("mclBn{}_setInt", name);
+    let set_le_mod_fn = ident!("mclBn{}_setLittleEndianMod", name);
+    let set_be_mod_fn = ident!("mclBn{}_setBigEndianMod", name);
+    let get_le_fn = ident!("mclBn{}_getLittleEndian", name);
+    let set_hash_fn = ident!("mclBn{}_setHashOf", name);
+    let is_odd_fn = ident!("mclBn{}_isOdd", name);
+
+    let inner_t = ident!("MclBn{}", name);
+
+    let expanded = quote! {
+        impl #name {
+            /// The multiplicative identity.
+            ///
+            /// # Panics
+            /// Panics if the library isn't initialized.
+            pub fn one() -> Self {
+                Self::from(1i64)
+            }
+
+            /// A fallible variant of [`Self::one`].
+            ///
+            /// # Errors
+            /// Returns [`MclError::NotInitialized`] if the library isn't initialized.
+            pub fn try_one() -> Result<Self, MclError> {
+                Self::try_from_i64(1)
+            }
+
+            /// A fallible variant of `From<i64>`.
+            ///
+            /// # Errors
+            /// Returns [`MclError::NotInitialized`] if the library isn't initialized.
+            pub fn try_from_i64(value: i64) -> Result<Self, MclError> {
+                crate::init::check_initialized()?;
+                let mut result = Self::default();
+                unsafe { #set_int_fn(&mut result.inner as *mut #inner_t, value) };
+                Ok(result)
+            }
+
+            /// A fallible variant of `From<u64>`.
+            ///
+            /// # Errors
+            /// Returns [`MclError::NotInitialized`] if the library isn't initialized.
+            pub fn try_from_u64(value: u64) -> Result<Self, MclError> {
+                Self::from_le_bytes_mod_order(&value.to_le_bytes())
+            }
+
+            /// The number of bytes of the little-endian representation.
+            pub fn byte_size() -> usize {
+                unsafe { #byte_size_fn() as usize }
+            }
+
+            /// Interpret `bytes` as a little-endian integer and reduce it modulo
+            /// the order of the field.
+            ///
+            /// # Errors
+            /// Returns [`MclError::InvalidEncoding`] if `bytes` is longer than
+            /// twice [`Self::byte_size`].
+            pub fn from_le_bytes_mod_order(bytes: &[u8]) -> Result<Self, MclError> {
+                crate::init::check_initialized()?;
+                let mut result = Self::default();
+                let err = unsafe {
+                    #set_le_mod_fn(
+                        &mut result.inner as *mut #inner_t,
+                        bytes.as_ptr() as *const std::os::raw::c_void,
+                        bytes.len(),
+                    )
+                };
+                match err {
+                    0 => Ok(result),
+                    _ => Err(MclError::InvalidEncoding),
+                }
+            }
+
+            /// Interpret `bytes` as a big-endian integer and reduce it modulo
+            /// the order of the field.
+            ///
+            /// # Errors
+            /// Returns [`MclError::InvalidEncoding`] if `bytes` is longer than
+            /// twice [`Self::byte_size`].
+            pub fn from_be_bytes_mod_order(bytes: &[u8]) -> Result<Self, MclError> {
+                crate::init::check_initialized()?;
+                let mut result = Self::default();
+                let err = unsafe {
+                    #set_be_mod_fn(
+                        &mut result.inner as *mut #inner_t,
+                        bytes.as_ptr() as *const std::os::raw::c_void,
+                        bytes.len(),
+                    )
+                };
+                match err {
+                    0 => Ok(result),
+                    _ => Err(MclError::InvalidEncoding),
+                }
+            }
+
+            /// Reduce 64 uniformly random bytes, read as a little-endian
+            /// integer, into a uniformly random element.
+            ///
+            /// The bias of the result is at most `2^-128` as long as the
+            /// modulus has at most 384 bits.
+            ///
+            /// # Panics
+            /// Panics if the library isn't initialized.
+            pub fn from_uniform_bytes(bytes: &[u8; 64]) -> Self {
+                assert!(crate::init::is_initialized(), "MCL library is not initialized");
+                Self::from_le_bytes_mod_order(bytes).expect("64 bytes are always accepted")
+            }
+
+            /// The little-endian representation padded to [`Self::byte_size`] bytes.
+            pub fn to_le_bytes(&self) -> Vec<u8> {
+                let mut buf = vec![0u8; Self::byte_size()];
+                let written = unsafe {
+                    #get_le_fn(
+                        buf.as_mut_ptr() as *mut std::os::raw::c_void,
+                        buf.len(),
+                        &self.inner as *const #inner_t,
+                    )
+                };
+                assert_ne!(written, 0, "the buffer fits any element of the field");
+                buf
+            }
+
+            /// Check whether the integer representation of the element is odd.
+            pub fn is_odd(&self) -> bool {
+                unsafe { #is_odd_fn(&self.inner as *const #inner_t) == 1 }
+            }
+
+            /// Hash `msg` and reduce the digest modulo the order of the field.
+            pub fn hash_to_scalar(msg: &[u8]) -> Result<Self, MclError> {
+                crate::init::check_initialized()?;
+                let mut result = Self::default();
+                let err = unsafe {
+                    #set_hash_fn(
+                        &mut result.inner as *mut #inner_t,
+                        msg.as_ptr() as *const std::os::raw::c_void,
+                        msg.len(),
+                    )
+                };
+                match err {
+                    0 => Ok(result),
+                    n => Err(MclError::Ffi(n)),
+                }
+            }
+        }
+
+        impl From<i64> for #name {
+            /// # Panics
+            /// Panics if the library isn't initialized.
+            fn from(value: i64) -> Self {
+                Self::try_from_i64(value).expect("MCL library is not initialized")
+            }
+        }
+
+        impl From<u64> for #name {
+            /// # Panics
+            /// Panics if the library isn't initialized.
+            fn from(value: u64) -> Self {
+                Self::try_from_u64(value).expect("MCL library is not initialized")
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+#[proc_macro_derive(Polynomial)]
+pub fn derive_polynomial(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let name = ast.ident;
+
+    let lagrange_fn = ident!("mclBn_{}LagrangeInterpolation", name);
+    let eval_fn = ident!("mclBn_{}EvaluatePolynomial", name);
+
+    let inner_t = ident!("MclBn{}", name);
+
+    let expanded = quote! {
+        impl #name {
+            /// Recover `f(0)` of the polynomial `f` of degree `xs.len() - 1`
+            /// passing through all `(xs[i], ys[i])`, e.g. a secret from its shares.
+            ///
+            /// # Errors
+            /// Returns [`MclError::LengthMismatch`] if the slices have different
+            /// lengths and [`MclError::InvalidInterpolation`] if `xs` is empty,
+            /// contains zero or contains duplicates.
+            pub fn lagrange_interpolation(xs: &[Fr], ys: &[Self]) -> Result<Self, MclError> {
+                if xs.len() != ys.len() {
+                    return Err(MclError::LengthMismatch);
+                }
+                // MCL doesn't check a single x-coordinate
+                if xs.iter().any(Fr::is_zero) {
+                    return Err(MclError::InvalidInterpolation);
+                }
+                let mut result = Self::default();
+                let err = unsafe {
+                    #lagrange_fn(
+                        &mut result.inner as *mut #inner_t,
+                        xs.as_ptr() as *const MclBnFr,
+                        ys.as_ptr() as *const #inner_t,
+                        xs.len(),
+                    )
+                };
+                match err {
+                    0 => Ok(result),
+                    _ => Err(MclError::InvalidInterpolation),
+                }
+            }
+
+            /// Evaluate `sum(coeffs[i] * x^i)`.
+            ///
+            /// Returns zero when `coeffs` is empty.
+            pub fn evaluate_polynomial(coeffs: &[Self], x: &Fr) -> Self {
+                let mut result = Self::default();
+                if coeffs.is_empty() {
+                    result.clear();
+                    return result;
+                }
+                unsafe {
+                    #eval_fn(
+                        &mut result.inner as *mut #inner_t,
+                        coeffs.as_ptr() as *const #inner_t,
+                        coeffs.len(),
+                        &x.inner as *const MclBnFr,
+                    );
+                }
+                result
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+// `#[affine_serialize]` marks the types with an uncompressed form written
+// with `MCLBN_IO_EC_AFFINE_SERIALIZE`.
+#[proc_macro_derive(Object, attributes(affine_serialize))]
 pub fn derive_mcl_object(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let name = ast.ident;
@@ -140,6 +584,52 @@ pub fn derive_mcl_object(input: TokenStream) -> TokenStream {
     let inner_t = ident!("MclBn{}", name);
     let visitor_struct = ident!("{}Visitor", name);
 
+    // Only the curve points have an uncompressed form, the other types
+    // are serialized in the same way as in `SerializationFormat::Eth`.
+    let affine_serialize = ast.attrs.iter().any(|attr| attr.path.is_ident("affine_serialize"));
+    let (serialize_uncompressed, deserialize_uncompressed) = if affine_serialize {
+        let get_str_fn = ident!("mclBn{}_getStr", name);
+        let set_str_fn = ident!("mclBn{}_setStr", name);
+        (
+            quote! {
+                #get_str_fn(
+                    buf.as_mut_ptr() as *mut std::os::raw::c_char,
+                    buf.len() as libc::size_t,
+                    &self.inner as *const #inner_t,
+                    MCLBN_IO_EC_AFFINE_SERIALIZE,
+                )
+            },
+            quote! {
+                match #set_str_fn(
+                    &mut self.inner as *mut #inner_t,
+                    bytes.as_ptr() as *const std::os::raw::c_char,
+                    bytes.len() as libc::size_t,
+                    MCLBN_IO_EC_AFFINE_SERIALIZE,
+                ) {
+                    0 => bytes.len(),
+                    _ => 0,
+                }
+            },
+        )
+    } else {
+        (
+            quote! {
+                #ser_fn(
+                    buf.as_mut_ptr() as *mut std::os::raw::c_void,
+                    buf.len() as libc::size_t,
+                    &self.inner as *const #inner_t,
+                )
+            },
+            quote! {
+                #de_fn(
+                    &mut self.inner as *mut #inner_t,
+                    bytes.as_ptr() as *const std::os::raw::c_void,
+                    bytes.len()
+                )
+            },
+        )
+    };
+
     let expanded = quote! {
         impl #name {
             pub fn clear(&mut self) {
@@ -149,37 +639,75 @@ pub fn derive_mcl_object(input: TokenStream) -> TokenStream {
             }
         }
 
-        impl RawSerializable for #name {
-            fn serialize_raw(&self) -> Result<Vec<u8>, ()> {
-                let mut buf = vec![0; 2048];
+        impl #name {
+            fn serialize_unlocked(&self, uncompressed: bool) -> Result<Vec<u8>, MclError> {
+                // wiped when dropped, as it may hold a serialized secret
+                let mut buf = zeroize::Zeroizing::new(vec![0; 2048]);
                 let bytes = unsafe {
-                    #ser_fn(
-                        buf.as_mut_ptr() as *mut std::os::raw::c_void,
-                        buf.len() as libc::size_t,
-                        &self.inner as *const #inner_t,
-                    )
+                    if uncompressed {
+                        #serialize_uncompressed
+                    } else {
+                        #ser_fn(
+                            buf.as_mut_ptr() as *mut std::os::raw::c_void,
+                            buf.len() as libc::size_t,
+                            &self.inner as *const #inner_t,
+                        )
+                    }
                 };
                 match bytes {
-                    0 => Err(()),
+                    0 => Err(MclError::BufferTooSmall),
                     _ => Ok(buf[..bytes].to_vec())
                 }
             }
 
-            fn deserialize_raw(&mut self, bytes: &[u8]) -> Result<usize, ()> {
+            fn deserialize_unlocked(&mut self, bytes: &[u8], uncompressed: bool) -> Result<usize, MclError> {
+                crate::init::check_initialized()?;
                 let copied = unsafe {
-                    #de_fn(
-                        &mut self.inner as *mut #inner_t,
-                        bytes.as_ptr() as *const std::os::raw::c_void,
-                        bytes.len()
-                    )
+                    if uncompressed {
+                        #deserialize_uncompressed
+                    } else {
+                        #de_fn(
+                            &mut self.inner as *mut #inner_t,
+                            bytes.as_ptr() as *const std::os::raw::c_void,
+                            bytes.len()
+                        )
+                    }
                 };
                 match copied {
-                    0 => Err(()),
+                    0 => Err(MclError::InvalidEncoding),
                     _ => Ok(copied)
                 }
             }
         }
 
+        impl RawSerializable for #name {
+            fn serialize_raw(&self) -> Result<Vec<u8>, MclError> {
+                crate::common::with_serialization_mode(None, || self.serialize_unlocked(false))
+            }
+
+            fn deserialize_raw(&mut self, bytes: &[u8]) -> Result<usize, MclError> {
+                crate::common::with_serialization_mode(None, || self.deserialize_unlocked(bytes, false))
+            }
+
+            fn serialize_with(&self, format: SerializationFormat) -> Result<Vec<u8>, MclError> {
+                let uncompressed = format == SerializationFormat::EthUncompressed;
+                crate::common::with_serialization_mode(Some(format), || {
+                    self.serialize_unlocked(uncompressed)
+                })
+            }
+
+            fn deserialize_with(
+                &mut self,
+                bytes: &[u8],
+                format: SerializationFormat,
+            ) -> Result<usize, MclError> {
+                let uncompressed = format == SerializationFormat::EthUncompressed;
+                crate::common::with_serialization_mode(Some(format), || {
+                    self.deserialize_unlocked(bytes, uncompressed)
+                })
+            }
+        }
+
         #[cfg(feature="serde_lib")]
         impl serde::Serialize for #name {
             fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -188,7 +716,7 @@ pub fn derive_mcl_object(input: TokenStream) -> TokenStream {
                 S::Error: serde::ser::Error,
             {
                 use serde::ser::Error;
-                let buf = self.serialize_raw().map_err(|_| S::Error::custom("Couldn't serialize MCL object"))?;
+                let buf = self.serialize_raw().map_err(S::Error::custom)?;
                 serializer.serialize_bytes(&buf)
             }
         }
@@ -209,7 +737,7 @@ pub fn derive_mcl_object(input: TokenStream) -> TokenStream {
                 E: serde::de::Error,
             {
                 let mut val = #name::default();
-                val.deserialize_raw(bytes).map_err(|_| E::custom("Invalid MCL object to deserialize"))?;
+                val.deserialize_raw(bytes).map_err(E::custom)?;
                 Ok(val)
             }
         }
@@ -231,18 +759,55 @@ pub fn derive_mcl_object(input: TokenStream) -> TokenStream {
             }
         }
 
+        impl std::convert::TryFrom<&[u8]> for #name {
+            type Error = MclError;
+
+            /// Deserialize the object, requiring that all of the bytes are consumed.
+            fn try_from(bytes: &[u8]) -> Result<Self, MclError> {
+                let mut result = Self::default();
+                match result.deserialize_raw(bytes)? {
+                    n if n == bytes.len() => Ok(result),
+                    _ => Err(MclError::InvalidEncoding),
+                }
+            }
+        }
+
+        impl std::convert::TryFrom<Vec<u8>> for #name {
+            type Error = MclError;
+
+            fn try_from(bytes: Vec<u8>) -> Result<Self, MclError> {
+                <Self as std::convert::TryFrom<&[u8]>>::try_from(&bytes[..])
+            }
+        }
+
+        impl From<&#name> for Vec<u8> {
+            /// # Panics
+            /// Panics if the serialization fails, which can't happen for
+            /// objects created after the library is initialized.
+            fn from(value: &#name) -> Vec<u8> {
+                value.serialize_raw().expect("MCL object serialization failed")
+            }
+        }
+
+        impl From<#name> for Vec<u8> {
+            fn from(value: #name) -> Vec<u8> {
+                Vec::from(&value)
+            }
+        }
+
         #[cfg(test)]
         mod #tests_name {
             use super::#name;
             use crate::traits::RawSerializable;
-            use crate::init::{ init_curve, Curve };
+            use crate::error::MclError;
+            use crate::init::{ pin_curve, Curve };
 
             #[test]
             fn test_deserialize_empty_vec() {
-                init_curve(Curve::Bls12_381);
+                let _guard = pin_curve(Curve::Bls12_381).unwrap();
                 let mut x = #name::default();
                 let deserialized =  x.deserialize_raw(&[]);
-                assert!(deserialized.is_err())
+                assert_eq!(deserialized, Err(MclError::InvalidEncoding));
             }
         }
     };
@@ -260,18 +825,18 @@ pub fn derive_scalar_group(input: TokenStream) -> TokenStream {
     let neg_fn = ident!("mclBn{}_neg", name);
     let inv_fn = ident!("mclBn{}_inv", name);
     let sqr_fn = ident!("mclBn{}_sqr", name);
+    let is_zero_fn = ident!("mclBn{}_isZero", name);
+    let is_one_fn = ident!("mclBn{}_isOne", name);
+    let sqrt_fn = ident!("mclBn{}_squareRoot", name);
 
     let expanded = quote! {
         impl Add for #name {
             type Output = #name;
 
             #[inline]
-            fn add(self, other: Self) -> Self {
-                let mut result = Self::default();
-                unsafe {
-                    #add_fn(&mut result.inner, &self.inner, &other.inner);
-                };
-                result
+            fn add(mut self, other: Self) -> Self {
+                self += &other;
+                self
             }
         }
 
@@ -279,12 +844,9 @@ pub fn derive_scalar_group(input: TokenStream) -> TokenStream {
             type Output = #name;
 
             #[inline]
-            fn sub(self, other: Self) -> Self {
-                 let mut result = Self::default();
-                 unsafe {
-                     #sub_fn(&mut result.inner, &self.inner, &other.inner);
-                 };
-                 result
+            fn sub(mut self, other: Self) -> Self {
+                self -= &other;
+                self
             }
         }
 
@@ -292,12 +854,9 @@ pub fn derive_scalar_group(input: TokenStream) -> TokenStream {
             type Output = #name;
 
             #[inline]
-            fn mul(self, other: Self) -> Self {
-                 let mut result = Self::default();
-                 unsafe {
-                     #mul_fn(&mut result.inner, &self.inner, &other.inner);
-                 };
-                 result
+            fn mul(mut self, other: Self) -> Self {
+                self *= &other;
+                self
             }
         }
 
@@ -305,16 +864,20 @@ pub fn derive_scalar_group(input: TokenStream) -> TokenStream {
             type Output = #name;
 
             #[inline]
-            fn div(self, other: Self) -> Self {
-                 let mut result = Self::default();
-                 unsafe {
-                     #div_fn(&mut result.inner, &self.inner, &other.inner);
-                 };
-                 result
+            fn div(mut self, other: Self) -> Self {
+                self /= &other;
+                self
             }
         }
 
         impl #name {
+            /// The additive identity, i.e. a cleared element.
+            pub fn zero() -> Self {
+                let mut result = Self::default();
+                result.clear();
+                result
+            }
+
             pub fn neg(&self) -> Self {
                 let mut result = Self::default();
                 unsafe {
@@ -338,6 +901,97 @@ pub fn derive_scalar_group(input: TokenStream) -> TokenStream {
                 };
                 result
             }
+
+            /// Raise the element to the power `e`.
+            ///
+            /// The running time depends on the exponent.
+            pub fn pow(&self, e: &Fr) -> Self {
+                self.pow_le_bytes(&e.to_le_bytes())
+            }
+
+            /// Raise the element to the power `e`.
+            ///
+            /// The running time depends on the exponent.
+            pub fn pow_u64(&self, e: u64) -> Self {
+                self.pow_le_bytes(&e.to_le_bytes())
+            }
+
+            // left-to-right square-and-multiply
+            fn pow_le_bytes(&self, e: &[u8]) -> Self {
+                let mut result = Self::one();
+                for byte in e.iter().rev() {
+                    for i in (0..8).rev() {
+                        let z = &mut result.inner as *mut _;
+                        unsafe { #sqr_fn(z, z as *const _) };
+                        if (byte >> i) & 1 == 1 {
+                            result *= self;
+                        }
+                    }
+                }
+                result
+            }
+
+            /// Invert all elements of `values` in place at the cost of a single
+            /// inversion using Montgomery's trick.
+            ///
+            /// Zero elements are left unchanged, as with [`Self::inv`].
+            pub fn batch_invert(values: &mut [Self]) {
+                // prefixes[i] is the product of the non-zero values[..i]
+                let mut prefixes = Vec::with_capacity(values.len());
+                let mut acc = Self::one();
+                for v in values.iter() {
+                    prefixes.push(acc);
+                    if !v.is_zero() {
+                        acc *= v;
+                    }
+                }
+                let mut acc_inv = acc.inv();
+                for (v, prefix) in values.iter_mut().zip(prefixes).rev() {
+                    if v.is_zero() {
+                        continue;
+                    }
+                    let v_inv = acc_inv * prefix;
+                    acc_inv *= &*v;
+                    *v = v_inv;
+                }
+            }
+
+            pub fn is_zero(&self) -> bool {
+                unsafe { #is_zero_fn(&self.inner) == 1 }
+            }
+
+            pub fn is_one(&self) -> bool {
+                unsafe { #is_one_fn(&self.inner) == 1 }
+            }
+
+            /// A square root of the element, or `None` if it's not a square.
+            ///
+            /// The other root is its negation.
+            pub fn sqrt(&self) -> Option<Self> {
+                let mut result = Self::default();
+                let err = unsafe { #sqrt_fn(&mut result.inner, &self.inner) };
+                match err {
+                    0 => Some(result),
+                    _ => None,
+                }
+            }
+
+            /// Check whether the element is a quadratic residue (zero included).
+            pub fn is_square(&self) -> bool {
+                self.sqrt().is_some()
+            }
+
+            /// The Legendre symbol: `0` for zero, `1` for non-zero squares
+            /// and `-1` otherwise.
+            pub fn legendre(&self) -> i32 {
+                if self.is_zero() {
+                    0
+                } else if self.is_square() {
+                    1
+                } else {
+                    -1
+                }
+            }
         }
 
     };
@@ -346,7 +1000,13 @@ pub fn derive_scalar_group(input: TokenStream) -> TokenStream {
     result.extend(forward_ref_binop! { impl Sub, sub for name, name });
     result.extend(forward_ref_binop! { impl Mul, mul for name, name });
     result.extend(forward_ref_binop! { impl Div, div for name, name });
-
+    result.extend(assign_op! { impl AddAssign, add_assign for name, name, add_fn });
+    result.extend(assign_op! { impl SubAssign, sub_assign for name, name, sub_fn });
+    result.extend(assign_op! { impl MulAssign, mul_assign for name, name, mul_fn });
+    result.extend(assign_op! { impl DivAssign, div_assign for name, name, div_fn });
+    result.extend(neg_op! { name, neg_fn });
+    result.extend(fold_op! { impl Sum, sum for name, zero, AddAssign, add_assign });
+    result.extend(fold_op! { impl Product, product for name, one, MulAssign, mul_assign });
     result
 }
 
@@ -360,8 +1020,14 @@ pub fn derive_additivte_group(input: TokenStream) -> TokenStream {
     let neg_fn = ident!("mclBn{}_neg", name);
     let dbl_fn = ident!("mclBn{}_dbl", name);
     let mul_fn = ident!("mclBn{}_mul", name);
+    let mul_ct_fn = ident!("mclBn{}_mulCT", name);
+    let mul_vec_fn = ident!("mclBn{}_mulVec", name);
+    let is_zero_fn = ident!("mclBn{}_isZero", name);
+    let is_valid_fn = ident!("mclBn{}_isValid", name);
+    let is_valid_order_fn = ident!("mclBn{}_isValidOrder", name);
 
     let hnm_fn = ident!("mclBn{}_hashAndMapTo", name);
+    let hnm_dst_fn = ident!("mclBn{}_hashAndMapToWithDst", name);
 
     let inner_t = ident!("MclBn{}", name);
 
@@ -370,12 +1036,9 @@ pub fn derive_additivte_group(input: TokenStream) -> TokenStream {
             type Output = #name;
 
             #[inline]
-            fn add(self, other: Self) -> Self {
-                let mut result = Self::default();
-                unsafe {
-                    #add_fn(&mut result.inner, &self.inner, &other.inner);
-                };
-                result
+            fn add(mut self, other: Self) -> Self {
+                self += &other;
+                self
             }
         }
 
@@ -383,16 +1046,25 @@ pub fn derive_additivte_group(input: TokenStream) -> TokenStream {
             type Output = #name;
 
             #[inline]
-            fn sub(self, other: Self) -> Self {
-                 let mut result = Self::default();
-                 unsafe {
-                     #sub_fn(&mut result.inner, &self.inner, &other.inner);
-                 };
-                 result
+            fn sub(mut self, other: Self) -> Self {
+                self -= &other;
+                self
             }
         }
 
         impl #name {
+            /// The point at infinity.
+            pub fn zero() -> Self {
+                let mut result = Self::default();
+                result.clear();
+                result
+            }
+
+            /// The identity of the group, an alias of [`Self::zero`].
+            pub fn identity() -> Self {
+                Self::zero()
+            }
+
             pub fn neg(&self) -> Self {
                 let mut result = Self::default();
                 unsafe {
@@ -409,19 +1081,124 @@ pub fn derive_additivte_group(input: TokenStream) -> TokenStream {
                 result
             }
 
-            pub fn hash_and_map(buf: &[u8]) -> Result<Self, i32> {
+            /// Check whether the point is the point at infinity.
+            pub fn is_zero(&self) -> bool {
+                unsafe { #is_zero_fn(&self.inner) == 1 }
+            }
+
+            /// Check whether the point is on the curve and, unless the order
+            /// verification was disabled, in the prime-order subgroup.
+            pub fn is_valid(&self) -> bool {
+                unsafe { #is_valid_fn(&self.inner) == 1 }
+            }
+
+            /// Check whether the point is in the prime-order subgroup.
+            pub fn is_valid_order(&self) -> bool {
+                unsafe { #is_valid_order_fn(&self.inner) == 1 }
+            }
+
+            /// A variant of [`RawSerializable::deserialize_raw`] that is safe
+            /// to use on untrusted input, e.g. public keys.
+            ///
+            /// On top of the checks of `deserialize_raw` it requires that all
+            /// of the bytes are consumed.
+            ///
+            /// # Errors
+            /// - [`MclError::InvalidEncoding`] if the bytes can't be deserialized,
+            /// - [`MclError::NotOnCurve`] if the point isn't on the curve,
+            /// - [`MclError::WrongSubgroup`] if the point isn't in the prime-order subgroup,
+            /// - [`MclError::IdentityPoint`] if the point is the point at infinity.
+            pub fn deserialize_raw_checked(&mut self, bytes: &[u8]) -> Result<usize, MclError> {
+                let mut point = Self::default();
+                let copied = point.deserialize_raw(bytes)?;
+                if copied != bytes.len() {
+                    return Err(MclError::InvalidEncoding);
+                }
+                if !point.is_valid() {
+                    return Err(MclError::NotOnCurve);
+                }
+                if !point.is_valid_order() {
+                    return Err(MclError::WrongSubgroup);
+                }
+                if point.is_zero() {
+                    return Err(MclError::IdentityPoint);
+                }
+                *self = point;
+                Ok(copied)
+            }
+
+            /// Hash `buf` onto the curve using the current map-to mode
+            /// (see [`crate::init::set_map_to_mode`]).
+            pub fn hash_and_map(buf: &[u8]) -> Result<Self, MclError> {
+                crate::init::check_initialized()?;
                 let mut result = Self::default();
-                let err = unsafe {
+                let err = crate::common::with_map_to_mode(None, || unsafe {
                     #hnm_fn(
                         &mut result.inner as *mut #inner_t,
                         buf.as_ptr() as *const std::os::raw::c_void,
                         buf.len(),
                     )
-                };
+                })?;
+                match err {
+                    0 => Ok(result),
+                    _ => Err(MclError::MapToCurve),
+                }
+            }
+
+            /// Hash `msg` onto the curve following the `hash_to_curve` function
+            /// of RFC 9380 with the domain separation tag `dst`, e.g. the
+            /// `BLS12381G1_XMD:SHA-256_SSWU_RO_` suite for `G1`.
+            ///
+            /// Available for BLS12-381 only.
+            ///
+            /// # Errors
+            /// Returns [`MclError::Ffi`] if the curve doesn't support the
+            /// hash-to-curve mode and [`MclError::MapToCurve`] if hashing fails.
+            pub fn hash_to_curve(msg: &[u8], dst: &[u8]) -> Result<Self, MclError> {
+                crate::init::check_initialized()?;
+                let mut result = Self::default();
+                let err = crate::common::with_map_to_mode(Some(MapToMode::HashToCurve), || unsafe {
+                    #hnm_dst_fn(
+                        &mut result.inner as *mut #inner_t,
+                        msg.as_ptr() as *const std::os::raw::c_void,
+                        msg.len(),
+                        dst.as_ptr() as *const std::os::raw::c_char,
+                        dst.len(),
+                    )
+                })?;
                 match err {
                     0 => Ok(result),
-                    n => Err(n),
+                    _ => Err(MclError::MapToCurve),
+                }
+            }
+
+            /// Compute `sum(points[i] * scalars[i])` using the multi-scalar
+            /// multiplication of MCL, which is much faster than a loop of `Mul`s.
+            ///
+            /// Returns zero when both slices are empty.
+            ///
+            /// # Errors
+            /// Returns [`MclError::LengthMismatch`] if the slices have different lengths.
+            pub fn mul_vec(points: &[Self], scalars: &[Fr]) -> Result<Self, MclError> {
+                if points.len() != scalars.len() {
+                    return Err(MclError::LengthMismatch);
+                }
+                let mut result = Self::default();
+                if points.is_empty() {
+                    result.clear();
+                    return Ok(result);
+                }
+                // MCL normalizes the points in place, so it gets a copy
+                let mut points = points.to_vec();
+                unsafe {
+                    #mul_vec_fn(
+                        &mut result.inner as *mut #inner_t,
+                        points.as_mut_ptr() as *mut #inner_t,
+                        scalars.as_ptr() as *const MclBnFr,
+                        scalars.len(),
+                    );
                 }
+                Ok(result)
             }
 
         }
@@ -430,15 +1207,44 @@ pub fn derive_additivte_group(input: TokenStream) -> TokenStream {
             type Output = #name;
 
             #[inline]
-            fn mul(self, other: Fr) -> Self {
+            fn mul(mut self, other: Fr) -> Self {
+                self *= &other;
+                self
+            }
+        }
+
+        impl #name {
+            /// Multiply the point by `x` in constant time.
+            ///
+            /// `Mul` is variable-time, use this function for secret scalars.
+            /// Multiplying by a [`crate::secret::SecretFr`] calls it implicitly.
+            pub fn mul_ct(&self, x: &Fr) -> Self {
                 let mut result = Self::default();
                 unsafe {
-                    #mul_fn(&mut result.inner, &self.inner, &other.inner);
-                };
+                    #mul_ct_fn(&mut result.inner, &self.inner, &x.inner);
+                }
                 result
             }
         }
 
+        impl<'a, 'b> Mul<&'a crate::secret::SecretFr> for &'b #name {
+            type Output = #name;
+
+            #[inline]
+            fn mul(self, other: &'a crate::secret::SecretFr) -> #name {
+                self.mul_ct(other.expose_secret())
+            }
+        }
+
+        impl<'a> Mul<&'a crate::secret::SecretFr> for #name {
+            type Output = #name;
+
+            #[inline]
+            fn mul(self, other: &'a crate::secret::SecretFr) -> #name {
+                self.mul_ct(other.expose_secret())
+            }
+        }
+
 
     };
     let fr_ident = Ident::new("Fr", Span::call_site());
@@ -446,6 +1252,11 @@ pub fn derive_additivte_group(input: TokenStream) -> TokenStream {
     result.extend(forward_ref_binop! { impl Add, add for name, name });
     result.extend(forward_ref_binop! { impl Sub, sub for name, name });
     result.extend(forward_ref_binop! { impl Mul, mul for name, fr_ident });
+    result.extend(assign_op! { impl AddAssign, add_assign for name, name, add_fn });
+    result.extend(assign_op! { impl SubAssign, sub_assign for name, name, sub_fn });
+    result.extend(assign_op! { impl MulAssign, mul_assign for name, fr_ident, mul_fn });
+    result.extend(neg_op! { name, neg_fn });
+    result.extend(fold_op! { impl Sum, sum for name, zero, AddAssign, add_assign });
     result
 }
 
@@ -455,22 +1266,80 @@ pub fn derive_multiplicative_group(input: TokenStream) -> TokenStream {
     let name = ast.ident;
 
     let mul_fn = ident!("mclBn{}_mul", name);
+    let div_fn = ident!("mclBn{}_div", name);
+    let inv_fn = ident!("mclBn{}_inv", name);
+    let inv_generic_fn = ident!("mclBn{}_invGeneric", name);
+    let sqr_fn = ident!("mclBn{}_sqr", name);
+    let pow_vec_fn = ident!("mclBn{}_powVec", name);
+    let set_int_fn = ident!("mclBn{}_setInt", name);
+    let is_zero_fn = ident!("mclBn{}_isZero", name);
+    let is_one_fn = ident!("mclBn{}_isOne", name);
+
+    let inner_t = ident!("MclBn{}", name);
 
     let expanded = quote! {
         impl Mul for #name {
             type Output = #name;
 
             #[inline]
-            fn mul(self, other: Self) -> Self {
-                 let mut result = Self::default();
-                 unsafe {
-                     #mul_fn(&mut result.inner, &self.inner, &other.inner);
-                 };
-                 result
+            fn mul(mut self, other: Self) -> Self {
+                self *= &other;
+                self
+            }
+        }
+
+        impl Div for #name {
+            type Output = #name;
+
+            /// Multiply by the inverse of `other`, computed with
+            /// [`Self::inv_generic`].
+            #[inline]
+            fn div(mut self, other: Self) -> Self {
+                self /= &other;
+                self
             }
         }
 
         impl #name {
+            /// The identity of the group.
+            pub fn one() -> Self {
+                let mut result = Self::default();
+                unsafe { #set_int_fn(&mut result.inner as *mut #inner_t, 1) };
+                result
+            }
+
+            /// The identity of the group, an alias of [`Self::one`].
+            pub fn identity() -> Self {
+                Self::one()
+            }
+
+            /// The inverse of an element of the cyclotomic subgroup, e.g. of a
+            /// pairing result, computed cheaply as its conjugate.
+            ///
+            /// The result is wrong for other elements, e.g. an unfinished
+            /// Miller loop, use [`Self::inv_generic`] for those.
+            pub fn inv(&self) -> Self {
+                let mut result = Self::default();
+                unsafe { #inv_fn(&mut result.inner, &self.inner) };
+                result
+            }
+
+            /// The inverse of any non-zero element.
+            pub fn inv_generic(&self) -> Self {
+                let mut result = Self::default();
+                unsafe { #inv_generic_fn(&mut result.inner, &self.inner) };
+                result
+            }
+
+            pub fn sqr(&self) -> Self {
+                let mut result = Self::default();
+                unsafe { #sqr_fn(&mut result.inner, &self.inner) };
+                result
+            }
+
+            /// Raise the element to the power `a`.
+            ///
+            /// The running time depends on `a`, use [`Self::pow_ct`] for secrets.
             pub fn pow(&self, a: &Fr) -> Self {
                 let mut result = MclBnGT::default();
                 unsafe {
@@ -482,10 +1351,68 @@ pub fn derive_multiplicative_group(input: TokenStream) -> TokenStream {
                 }
                 GT { inner: result }
             }
+
+            /// Raise the element to the power `a` in constant time.
+            pub fn pow_ct(&self, a: &Fr) -> Self {
+                let mut result = Self::default();
+                unsafe {
+                    mclBnGT_powCT(
+                        &mut result.inner as *mut MclBnGT,
+                        &self.inner as *const MclBnGT,
+                        &a.inner as *const MclBnFr,
+                    );
+                }
+                result
+            }
+
+            /// Raise the element to the power of a secret exponent, e.g. a
+            /// private key, with [`Self::pow_ct`].
+            pub fn pow_secret(&self, a: &crate::secret::SecretFr) -> Self {
+                self.pow_ct(a.expose_secret())
+            }
+
+            /// Compute `prod(bases[i] ^ exponents[i])` in a single call.
+            ///
+            /// Returns one when both slices are empty.
+            ///
+            /// # Errors
+            /// Returns [`MclError::LengthMismatch`] if the slices have different lengths.
+            pub fn pow_vec(bases: &[Self], exponents: &[Fr]) -> Result<Self, MclError> {
+                if bases.len() != exponents.len() {
+                    return Err(MclError::LengthMismatch);
+                }
+                let mut result = Self::default();
+                unsafe {
+                    if bases.is_empty() {
+                        #set_int_fn(&mut result.inner as *mut #inner_t, 1);
+                    } else {
+                        #pow_vec_fn(
+                            &mut result.inner as *mut #inner_t,
+                            bases.as_ptr() as *const #inner_t,
+                            exponents.as_ptr() as *const MclBnFr,
+                            exponents.len(),
+                        );
+                    }
+                }
+                Ok(result)
+            }
+
+            pub fn is_zero(&self) -> bool {
+                unsafe { #is_zero_fn(&self.inner) == 1 }
+            }
+
+            /// Check whether the element is the identity of the group.
+            pub fn is_one(&self) -> bool {
+                unsafe { #is_one_fn(&self.inner) == 1 }
+            }
         }
     };
 
     let mut result = TokenStream::from(expanded);
     result.extend(forward_ref_binop! { impl Mul, mul for name, name });
+    result.extend(forward_ref_binop! { impl Div, div for name, name });
+    result.extend(assign_op! { impl MulAssign, mul_assign for name, name, mul_fn });
+    result.extend(assign_op! { impl DivAssign, div_assign for name, name, div_fn });
+    result.extend(fold_op! { impl Product, product for name, one, MulAssign, mul_assign });
     result
 }